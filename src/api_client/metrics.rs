@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+/// Measurements of a single finished query.
+///
+/// Given to [`Metrics::query_finished`] by the API clients.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QueryMeasurement {
+	/// The type name of the query, such as `neos::query::friends::Friends`
+	pub query: &'static str,
+	/// The HTTP method of the request, such as `GET`
+	pub method: &'static str,
+	/// The HTTP status code of the response.
+	///
+	/// None if the request failed before a response was received.
+	pub status: Option<u16>,
+	/// If the query succeeded, including deserializing the response
+	pub is_success: bool,
+	/// How long sending the request and receiving the response took
+	pub latency: Duration,
+	/// How long the request waited for the rate limiter
+	pub rate_limit_wait: Duration,
+}
+
+/// A hook for collecting metrics about the queries that API clients make.
+///
+/// # Example usage
+///
+/// ```no_run
+/// # use neos::api_client::{Metrics, QueryMeasurement, UnauthenticatedNeos};
+/// # let USER_AGENT = String::new();
+/// struct PrintMetrics;
+///
+/// impl Metrics for PrintMetrics {
+/// 	fn query_finished(&self, measurement: &QueryMeasurement) {
+/// 		println!("{} took {:?}", measurement.query, measurement.latency);
+/// 	}
+/// }
+///
+/// let neos_api_client =
+/// 	UnauthenticatedNeos::new(USER_AGENT).unwrap().with_metrics(PrintMetrics);
+/// ```
+pub trait Metrics: Send + Sync {
+	/// Called after every query, whether it succeeded or not
+	fn query_finished(&self, measurement: &QueryMeasurement);
+}
+
+/// Allows keeping a handle to the metrics that are given to a client.
+impl<T: Metrics + ?Sized> Metrics for std::sync::Arc<T> {
+	fn query_finished(&self, measurement: &QueryMeasurement) {
+		(**self).query_finished(measurement);
+	}
+}
+
+#[cfg(feature = "metrics")]
+/// Reports the query measurements with the [`metrics`](::metrics) crate.
+///
+/// The metrics are labelled with the `query` type, `method` and `status`:
+///
+/// - `neos_queries_total` counter of all queries
+/// - `neos_query_errors_total` counter of failed queries
+/// - `neos_query_latency_seconds` histogram of the request latencies
+/// - `neos_query_rate_limit_wait_seconds` histogram of the rate limit waits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MetricsFacade;
+
+#[cfg(feature = "metrics")]
+impl Metrics for MetricsFacade {
+	fn query_finished(&self, measurement: &QueryMeasurement) {
+		let status =
+			measurement.status.map_or_else(|| "none".to_owned(), |s| s.to_string());
+		let labels = [
+			("query", measurement.query.to_owned()),
+			("method", measurement.method.to_owned()),
+			("status", status),
+		];
+
+		::metrics::counter!("neos_queries_total", &labels).increment(1);
+		if !measurement.is_success {
+			::metrics::counter!("neos_query_errors_total", &labels).increment(1);
+		}
+		::metrics::histogram!("neos_query_latency_seconds", &labels)
+			.record(measurement.latency.as_secs_f64());
+		::metrics::histogram!("neos_query_rate_limit_wait_seconds", &labels)
+			.record(measurement.rate_limit_wait.as_secs_f64());
+	}
+}
@@ -16,8 +16,21 @@
 //! 2. [`neos::model::UserSession`](crate::model::UserSession)
 //!
 //! > Requires the `Authorization` header in addition to the rate limiting.
+//!
+//! The clients can also record their requests and replay them later, see
+//! [`Transport`](Transport).
+//!
+//! With the optional `tracing` feature, every query is wrapped in a
+//! `neos_query` span. The span has the query's type, method, URL path,
+//! response status, latency and time spent waiting for the rate limiter.
+//! Request headers and bodies are never included, and secrets in the path are
+//! redacted, so that the tokens don't end up in the logs.
+//!
+//! Metrics of the queries can be collected by giving the clients an
+//! implementation of the [`Metrics`](Metrics) trait. The optional `metrics`
+//! feature provides one for the [`metrics`](::metrics) crate.
 
-use std::num::NonZeroU32;
+use std::{num::NonZeroU32, sync::Arc};
 
 use governor::{
 	clock::DefaultClock,
@@ -27,10 +40,17 @@ use governor::{
 	RateLimiter,
 };
 pub use racal::reqwest::{ApiClient, ApiError};
+use racal::{FromApiState, Queryable};
 use reqwest::{header::HeaderMap, Client, RequestBuilder};
+use serde::de::DeserializeOwned;
 
 use crate::query::{Authentication, NoAuthentication};
 
+mod metrics;
+pub use metrics::*;
+mod transport;
+pub use transport::*;
+
 type NormalRateLimiter =
 	RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
 
@@ -49,6 +69,8 @@ pub struct UnauthenticatedNeos {
 	user_agent: String,
 	http: Client,
 	rate_limiter: NormalRateLimiter,
+	transport: Transport,
+	metrics: Option<Arc<dyn Metrics>>,
 }
 
 #[async_trait::async_trait]
@@ -63,6 +85,24 @@ impl ApiClient<NoAuthentication> for UnauthenticatedNeos {
 		self.rate_limiter.until_ready().await;
 		Ok(req)
 	}
+
+	async fn query<ReturnType, FromState, QueryableType>(
+		&self, queryable: QueryableType,
+	) -> Result<ReturnType, ApiError>
+	where
+		ReturnType: DeserializeOwned,
+		FromState: FromApiState<NoAuthentication>,
+		QueryableType: Queryable<FromState, ReturnType> + Send + Sync,
+	{
+		transport::query(
+			self,
+			&self.transport,
+			self.metrics.as_deref(),
+			None,
+			queryable,
+		)
+		.await
+	}
 }
 
 /// The main API client with authentication
@@ -71,6 +111,8 @@ pub struct AuthenticatedNeos {
 	http: Client,
 	rate_limiter: NormalRateLimiter,
 	auth: Authentication,
+	transport: Transport,
+	metrics: Option<Arc<dyn Metrics>>,
 }
 
 #[async_trait::async_trait]
@@ -85,6 +127,24 @@ impl ApiClient<Authentication> for AuthenticatedNeos {
 		self.rate_limiter.until_ready().await;
 		Ok(req)
 	}
+
+	async fn query<ReturnType, FromState, QueryableType>(
+		&self, queryable: QueryableType,
+	) -> Result<ReturnType, ApiError>
+	where
+		ReturnType: DeserializeOwned,
+		FromState: FromApiState<Authentication>,
+		QueryableType: Queryable<FromState, ReturnType> + Send + Sync,
+	{
+		transport::query(
+			self,
+			&self.transport,
+			self.metrics.as_deref(),
+			Some(&self.auth.token),
+			queryable,
+		)
+		.await
+	}
 }
 
 impl AuthenticatedNeos {
@@ -119,6 +179,8 @@ impl AuthenticatedNeos {
 			http: UnauthenticatedNeos::http_client(&self.user_agent)?,
 			rate_limiter: self.rate_limiter,
 			user_agent: self.user_agent,
+			transport: self.transport,
+			metrics: self.metrics,
 		})
 	}
 
@@ -136,8 +198,24 @@ impl AuthenticatedNeos {
 			rate_limiter: http_rate_limiter(),
 			user_agent,
 			auth,
+			transport: Transport::default(),
+			metrics: None,
 		})
 	}
+
+	#[must_use]
+	/// Sets the way that the requests are sent, for recording or replaying them
+	pub fn with_transport(mut self, transport: Transport) -> Self {
+		self.transport = transport;
+		self
+	}
+
+	#[must_use]
+	/// Sets the hook that collects metrics about the queries
+	pub fn with_metrics(mut self, metrics: impl Metrics + 'static) -> Self {
+		self.metrics = Some(Arc::new(metrics));
+		self
+	}
 }
 
 impl UnauthenticatedNeos {
@@ -160,6 +238,8 @@ impl UnauthenticatedNeos {
 			rate_limiter: self.rate_limiter,
 			user_agent: self.user_agent,
 			auth,
+			transport: self.transport,
+			metrics: self.metrics,
 		})
 	}
 
@@ -173,6 +253,22 @@ impl UnauthenticatedNeos {
 			http: Self::http_client(&user_agent)?,
 			rate_limiter: http_rate_limiter(),
 			user_agent,
+			transport: Transport::default(),
+			metrics: None,
 		})
 	}
+
+	#[must_use]
+	/// Sets the way that the requests are sent, for recording or replaying them
+	pub fn with_transport(mut self, transport: Transport) -> Self {
+		self.transport = transport;
+		self
+	}
+
+	#[must_use]
+	/// Sets the hook that collects metrics about the queries
+	pub fn with_metrics(mut self, metrics: impl Metrics + 'static) -> Self {
+		self.metrics = Some(Arc::new(metrics));
+		self
+	}
 }
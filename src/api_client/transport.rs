@@ -0,0 +1,437 @@
+use std::{
+	sync::{Arc, Mutex, PoisonError},
+	time::{Duration, Instant},
+};
+
+use racal::{
+	reqwest::{ApiClient, ApiError},
+	FromApiState,
+	Queryable,
+	RequestMethod,
+};
+use reqwest::{RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{Metrics, QueryMeasurement};
+
+/// JSON keys whose string values are replaced when recording interactions.
+const SCRUBBED_KEYS: &[&str] =
+	&["token", "password", "secretMachineId", "totp"];
+
+/// What is put in the place of scrubbed secrets in recorded interactions.
+const SCRUBBED_VALUE: &str = "*****";
+
+/// Path segments that are followed by a secret, and how many segments after
+/// them it is.
+const SECRET_PATH_SEGMENTS: &[(&str, usize)] = &[("userSessions", 2)];
+
+/// How the API clients send their requests.
+///
+/// # Example usage
+///
+/// ```no_run
+/// # use std::sync::{Arc, Mutex};
+/// # use neos::api_client::{Cassette, Transport, UnauthenticatedNeos};
+/// # let USER_AGENT = String::new();
+/// let cassette = Arc::new(Mutex::new(Cassette::default()));
+/// let neos_api_client = UnauthenticatedNeos::new(USER_AGENT)
+/// 	.unwrap()
+/// 	.with_transport(Transport::Record(cassette.clone()));
+/// // Do some queries with the client, and then save them for later replaying
+/// cassette.lock().unwrap().save("cassette.json").unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub enum Transport {
+	#[default]
+	/// Sends the requests to the API
+	Live,
+	/// Sends the requests to the API and records them into the cassette.
+	///
+	/// The authentication token, the values of secret JSON keys and the secrets
+	/// in URL paths are scrubbed from the recordings. Request headers, such as
+	/// `Authorization`, aren't recorded at all.
+	Record(Arc<Mutex<Cassette>>),
+	/// Serves the responses from the cassette without contacting the API.
+	///
+	/// Interactions are consumed from the cassette as they're replayed.
+	Replay(Arc<Mutex<Cassette>>),
+}
+
+impl Transport {
+	#[must_use]
+	/// If the requests are actually sent to the API
+	pub const fn is_live(&self) -> bool { !matches!(self, Self::Replay(_)) }
+}
+
+/// A recorded request and its response
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Interaction {
+	/// The HTTP method of the request, such as `GET`
+	pub method: String,
+	/// The URL that the request was sent to
+	pub url: String,
+	/// The body of the request, if it had one
+	pub body: Option<String>,
+	/// The HTTP status code of the response
+	pub status: u16,
+	/// The body of the response
+	pub response: String,
+}
+
+impl Interaction {
+	/// Creates a new interaction with any known secrets scrubbed out.
+	fn new(
+		method: &RequestMethod, url: &str, body: Option<&[u8]>, status: u16,
+		response: &[u8], secret: Option<&str>,
+	) -> Self {
+		let mut interaction = Self {
+			method: method_name(method).to_owned(),
+			url: scrub_url(url),
+			body: body.map(scrub_json_keys),
+			status,
+			response: scrub_json_keys(response),
+		};
+		if let Some(secret) = secret {
+			interaction.scrub(secret);
+		}
+		interaction
+	}
+
+	/// Replaces all occurrences of the secret in the interaction
+	fn scrub(&mut self, secret: &str) {
+		if secret.is_empty() {
+			return;
+		}
+		self.url = self.url.replace(secret, SCRUBBED_VALUE);
+		if let Some(body) = &mut self.body {
+			*body = body.replace(secret, SCRUBBED_VALUE);
+		}
+		self.response = self.response.replace(secret, SCRUBBED_VALUE);
+	}
+}
+
+/// A collection of recorded interactions with the API.
+///
+/// Serialized as JSON, so that they can be stored as test fixtures.
+#[derive(
+	Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub struct Cassette {
+	/// The recorded interactions, in the order they happened in
+	pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+	/// Loads a cassette from a JSON file
+	///
+	/// # Errors
+	///
+	/// If reading or deserializing the file fails
+	pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+		let data = std::fs::read(path)?;
+		Ok(serde_json::from_slice(&data)?)
+	}
+
+	/// Saves the cassette into a JSON file
+	///
+	/// # Errors
+	///
+	/// If serializing or writing the file fails
+	pub fn save(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+		let data = serde_json::to_vec_pretty(self)?;
+		std::fs::write(path, data)
+	}
+
+	/// Replaces all occurrences of the secret in the recorded interactions.
+	///
+	/// Values of keys such as `token` and `password` are scrubbed
+	/// automatically when recording, this is for anything else.
+	pub fn scrub(&mut self, secret: &str) {
+		for interaction in &mut self.interactions {
+			interaction.scrub(secret);
+		}
+	}
+
+	/// Takes out the first interaction matching the request
+	fn take(&mut self, method: &RequestMethod, url: &str) -> Option<Interaction> {
+		let method = method_name(method);
+		let url = scrub_url(url);
+		let index = self.interactions.iter().position(|interaction| {
+			interaction.method == method && interaction.url == url
+		})?;
+		Some(self.interactions.remove(index))
+	}
+}
+
+/// Creates a response out of already received parts
+fn build_response(
+	status: u16, body: impl Into<reqwest::Body>,
+) -> Result<Response, ApiError> {
+	use serde::de::Error;
+
+	let response =
+		http::Response::builder().status(status).body(body).map_err(|_| {
+			serde_json::Error::custom("Couldn't recreate recorded response")
+		})?;
+
+	Ok(Response::from(response))
+}
+
+#[cfg(feature = "tracing")]
+/// The path part of the URL, without the query string or secrets
+fn url_path(url: &str) -> String {
+	let url = url.strip_prefix(crate::API_BASE_URI).unwrap_or(url);
+	scrub_path(url.split_once('?').map_or(url, |(path, _)| path))
+}
+
+/// Replaces the secret segments of the path, such as the token in
+/// `userSessions/{user_id}/{token}`.
+fn scrub_path(path: &str) -> String {
+	let mut segments: Vec<&str> = path.split('/').collect();
+	for (name, offset) in SECRET_PATH_SEGMENTS {
+		if let Some(index) = segments.iter().position(|segment| segment == name) {
+			if let Some(segment) = segments.get_mut(index + offset) {
+				*segment = SCRUBBED_VALUE;
+			}
+		}
+	}
+	segments.join("/")
+}
+
+/// Replaces the secret segments of the URL's path, keeping the query string
+fn scrub_url(url: &str) -> String {
+	match url.split_once('?') {
+		Some((path, query)) => format!("{}?{query}", scrub_path(path)),
+		None => scrub_path(url),
+	}
+}
+
+const fn method_name(method: &RequestMethod) -> &'static str {
+	match method {
+		RequestMethod::Head => "HEAD",
+		RequestMethod::Get => "GET",
+		RequestMethod::Post => "POST",
+		RequestMethod::Put => "PUT",
+		RequestMethod::Patch => "PATCH",
+		RequestMethod::Delete => "DELETE",
+	}
+}
+
+/// Turns the data into a string, scrubbing secret keys if it's JSON.
+fn scrub_json_keys(data: &[u8]) -> String {
+	fn scrub(value: &mut serde_json::Value) {
+		match value {
+			serde_json::Value::Object(map) => {
+				for (key, value) in map.iter_mut() {
+					if value.is_string() && SCRUBBED_KEYS.contains(&key.as_str()) {
+						*value = SCRUBBED_VALUE.into();
+					} else {
+						scrub(value);
+					}
+				}
+			}
+			serde_json::Value::Array(values) => values.iter_mut().for_each(scrub),
+			_ => {}
+		}
+	}
+
+	match serde_json::from_slice::<serde_json::Value>(data) {
+		Ok(mut value) if value.is_object() || value.is_array() => {
+			scrub(&mut value);
+			value.to_string()
+		}
+		_ => String::from_utf8_lossy(data).into_owned(),
+	}
+}
+
+/// Sends the query with the transport, otherwise like the default
+/// [`ApiClient::query`](racal::reqwest::ApiClient::query).
+///
+/// The secret is the authentication token, which is scrubbed from recordings.
+pub(super) async fn query<State, Client, ReturnType, FromState, QueryableType>(
+	api: &Client, transport: &Transport, metrics: Option<&dyn Metrics>,
+	secret: Option<&str>, queryable: QueryableType,
+) -> Result<ReturnType, ApiError>
+where
+	Client: ApiClient<State> + Sync,
+	ReturnType: DeserializeOwned,
+	FromState: FromApiState<State>,
+	QueryableType: Queryable<FromState, ReturnType> + Send + Sync,
+{
+	let (method, url, body, request) = {
+		let state = FromState::from_state(api.state());
+		(
+			queryable.method(state),
+			queryable.url(state),
+			queryable.body(state).transpose()?,
+			Client::build_request(api.client(), state, &queryable)?,
+		)
+	};
+
+	let mut measurement = QueryMeasurement {
+		query: std::any::type_name::<QueryableType>(),
+		method: method_name(&method),
+		status: None,
+		is_success: false,
+		latency: Duration::ZERO,
+		rate_limit_wait: Duration::ZERO,
+	};
+
+	// Never include the headers or the body, they might contain secrets.
+	#[cfg(feature = "tracing")]
+	let span = tracing::info_span!(
+		"neos_query",
+		query = measurement.query,
+		method = measurement.method,
+		path = url_path(&url),
+		status = tracing::field::Empty,
+		latency_ms = tracing::field::Empty,
+		rate_limit_wait_ms = tracing::field::Empty,
+	);
+
+	let query = async move {
+		let response = send(
+			api,
+			transport,
+			&method,
+			url,
+			body,
+			request,
+			secret,
+			&mut measurement,
+		)
+		.await;
+		measurement.status =
+			response.as_ref().ok().map(|response| response.status().as_u16());
+
+		#[cfg(feature = "tracing")]
+		{
+			let span = tracing::Span::current();
+			span.record("latency_ms", measurement.latency.as_millis());
+			span
+				.record("rate_limit_wait_ms", measurement.rate_limit_wait.as_millis());
+			match &response {
+				Ok(response) => {
+					span.record("status", response.status().as_u16());
+					tracing::debug!("query finished");
+				}
+				Err(err) => tracing::warn!(error = %err, "query failed"),
+			}
+		}
+
+		let result = match response {
+			Ok(response) => api.handle_response(queryable, response).await,
+			Err(err) => Err(err),
+		};
+
+		if let Some(metrics) = metrics {
+			measurement.is_success = result.is_ok();
+			metrics.query_finished(&measurement);
+		}
+
+		result
+	};
+
+	#[cfg(feature = "tracing")]
+	let query = tracing::Instrument::instrument(query, span);
+
+	query.await
+}
+
+/// Gets the response either from the API or from the cassette
+#[allow(clippy::too_many_arguments)]
+async fn send<State, Client>(
+	api: &Client, transport: &Transport, method: &RequestMethod, url: String,
+	body: Option<Vec<u8>>, request: RequestBuilder, secret: Option<&str>,
+	measurement: &mut QueryMeasurement,
+) -> Result<Response, ApiError>
+where
+	Client: ApiClient<State> + Sync,
+{
+	if let Transport::Replay(cassette) = transport {
+		use serde::de::Error;
+
+		let interaction = cassette
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.take(method, &url)
+			.ok_or_else(|| {
+				serde_json::Error::custom(format!(
+					"No recorded interaction for {} {url}",
+					method_name(method)
+				))
+			})?;
+		return build_response(interaction.status, interaction.response);
+	}
+
+	let start = Instant::now();
+	let request = api.before_request(request).await?;
+	measurement.rate_limit_wait = start.elapsed();
+
+	let start = Instant::now();
+	let response = request.send().await;
+	measurement.latency = start.elapsed();
+	let response = response?;
+
+	let Transport::Record(cassette) = transport else {
+		return Ok(response);
+	};
+
+	let status = response.status().as_u16();
+	let data = response.bytes().await?;
+	measurement.latency = start.elapsed();
+	let interaction =
+		Interaction::new(method, &url, body.as_deref(), status, &data, secret);
+	cassette
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.interactions
+		.push(interaction);
+	build_response(status, data)
+}
+
+#[cfg(test)]
+mod tests {
+	use racal::RequestMethod;
+
+	#[test]
+	fn scrubs_recordings() {
+		let url =
+			format!("{}/userSessions/U-user/session-token?a=b", crate::API_BASE_URI);
+		let interaction = super::Interaction::new(
+			&RequestMethod::Delete,
+			&url,
+			Some(br#"{"password":"hunter2","note":"auth-token"}"#),
+			200,
+			b"auth-token",
+			Some("auth-token"),
+		);
+		assert_eq!(
+			interaction.url,
+			format!("{}/userSessions/U-user/*****?a=b", crate::API_BASE_URI)
+		);
+		assert_eq!(
+			interaction.body.as_deref(),
+			Some(r#"{"note":"*****","password":"*****"}"#)
+		);
+		assert_eq!(interaction.response, "*****");
+
+		let mut cassette = super::Cassette { interactions: vec![interaction] };
+		assert!(cassette.take(&RequestMethod::Get, &url).is_none());
+		assert!(cassette.take(&RequestMethod::Delete, &url).is_some());
+	}
+	#[cfg(feature = "tracing")]
+	#[test]
+	fn url_path() {
+		assert_eq!(
+			super::url_path(&format!(
+				"{}/userSessions/U-user/secret?a=b",
+				crate::API_BASE_URI
+			)),
+			"/userSessions/U-user/*****"
+		);
+		assert_eq!(super::url_path("/userSessions/U-user"), "/userSessions/U-user");
+		assert_eq!(super::url_path("/users/U-user"), "/users/U-user");
+	}
+}
@@ -55,7 +55,7 @@ impl TryFrom<&str> for AssetUrl {
 			return Self::from_url(url, false);
 		}
 
-		Err(concat!("should start with `neosdb:///` `https://`"))
+		Err("should start with `neosdb:///` `https://`")
 	}
 }
 
@@ -74,16 +74,17 @@ impl AssetUrl {
 	pub fn id(&self) -> &str { &self.id }
 
 	#[must_use]
+	#[allow(clippy::ref_option)]
 	/// Gets the extension
 	pub const fn ext(&self) -> &Option<String> { &self.ext }
 }
 
-impl ToString for AssetUrl {
+impl std::fmt::Display for AssetUrl {
 	/// The `https://` URL needed to retrieve the asset.
-	fn to_string(&self) -> String {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match (self.is_neosdb, &self.ext) {
-			(false, Some(ext)) => self.url_prefix.clone() + &self.id + "." + ext,
-			_ => self.url_prefix.clone() + &self.id,
+			(false, Some(ext)) => write!(f, "{}{}.{}", self.url_prefix, self.id, ext),
+			_ => write!(f, "{}{}", self.url_prefix, self.id),
 		}
 	}
 }
@@ -95,13 +96,13 @@ impl<'de> serde::de::Deserialize<'de> for AssetUrl {
 	{
 		struct IdVisitor;
 
-		impl<'de> serde::de::Visitor<'de> for IdVisitor {
+		impl serde::de::Visitor<'_> for IdVisitor {
 			type Value = AssetUrl;
 
 			fn expecting(
 				&self, formatter: &mut std::fmt::Formatter,
 			) -> std::fmt::Result {
-				formatter.write_str(concat!("an AssetUrl string"))
+				formatter.write_str("an AssetUrl string")
 			}
 
 			fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
@@ -0,0 +1,369 @@
+//! Grouping messages into per-contact conversations.
+//!
+//! The [`Conversations`] keeps the messages of the logged in user sorted into
+//! threads by the other user, de-duplicated by their IDs. New pages of
+//! [`Messages`](crate::query::Messages) results and sent messages can be merged
+//! in as they come, and the threads can be persisted with a
+//! [`ConversationStore`].
+//!
+//! # Example usage
+//!
+//! ```
+//! # use neos::conversations::{Conversations, MemoryStore};
+//! let mut store = MemoryStore::default();
+//! let mut conversations = Conversations::load(&store).unwrap();
+//! // Normally you'd get the messages by querying the API with
+//! // `conversations.next_query()`
+//! let messages: Vec<neos::model::Message> = Vec::new();
+//! conversations.merge(messages);
+//! for conversation in conversations.by_latest() {
+//! 	println!(
+//! 		"{}: {} unread",
+//! 		conversation.contact(),
+//! 		conversation.unread_count()
+//! 	);
+//! }
+//! conversations.save(&mut store).unwrap();
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use time::OffsetDateTime;
+
+use crate::model::Message;
+
+/// The messages with a single contact, sorted from the oldest to the newest
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversation {
+	contact: crate::id::User,
+	messages: Vec<Message>,
+}
+
+impl Conversation {
+	#[must_use]
+	/// Creates an empty conversation with the contact
+	pub const fn new(contact: crate::id::User) -> Self {
+		Self { contact, messages: Vec::new() }
+	}
+
+	#[must_use]
+	/// The other user of the conversation
+	pub const fn contact(&self) -> &crate::id::User { &self.contact }
+
+	#[must_use]
+	/// The messages, sorted from the oldest to the newest
+	pub fn messages(&self) -> &[Message] { &self.messages }
+
+	#[must_use]
+	/// The newest message of the conversation
+	pub fn last_message(&self) -> Option<&Message> { self.messages.last() }
+
+	/// The messages from the contact that haven't been read yet
+	pub fn unread(&self) -> impl Iterator<Item = &Message> {
+		self.messages.iter().filter(|message| {
+			message.read_time.is_none() && message.sender_id == self.contact
+		})
+	}
+
+	#[must_use]
+	/// The amount of messages from the contact that haven't been read yet
+	pub fn unread_count(&self) -> usize { self.unread().count() }
+
+	/// Locally marks the messages from the contact as read, returning their IDs
+	/// so that they can be marked as read with the API too.
+	pub fn mark_read(
+		&mut self, read_time: OffsetDateTime,
+	) -> Vec<crate::id::Message> {
+		let contact = &self.contact;
+		self
+			.messages
+			.iter_mut()
+			.filter(|message| {
+				message.read_time.is_none() && &message.sender_id == contact
+			})
+			.map(|message| {
+				message.read_time = Some(read_time);
+				message.id.clone()
+			})
+			.collect()
+	}
+
+	/// Adds the message, or replaces the existing one with the same ID if the
+	/// message has been updated since.
+	///
+	/// Returns if the conversation changed.
+	pub fn insert(&mut self, message: Message) -> bool {
+		if let Some(index) =
+			self.messages.iter().position(|existing| existing.id == message.id)
+		{
+			if self.messages[index].last_update_time >= message.last_update_time {
+				return false;
+			}
+			self.messages.remove(index);
+		}
+
+		let index = self.messages.partition_point(|existing| {
+			(existing.send_time, existing.id.as_ref())
+				<= (message.send_time, message.id.as_ref())
+		});
+		self.messages.insert(index, message);
+		true
+	}
+}
+
+/// Something that the messages of conversations can be persisted in
+pub trait ConversationStore {
+	/// The error that can happen when loading or saving
+	type Error;
+
+	/// Loads all of the stored messages
+	///
+	/// # Errors
+	///
+	/// If the messages couldn't be loaded
+	fn load(&self) -> Result<Vec<Message>, Self::Error>;
+
+	/// Stores the messages of a conversation, replacing the previously stored
+	/// ones of it
+	///
+	/// # Errors
+	///
+	/// If the messages couldn't be stored
+	fn save(
+		&mut self, contact: &crate::id::User, messages: &[Message],
+	) -> Result<(), Self::Error>;
+}
+
+/// Stores the conversations in memory
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryStore {
+	/// The stored messages by the contact
+	pub conversations: HashMap<crate::id::User, Vec<Message>>,
+}
+
+impl ConversationStore for MemoryStore {
+	type Error = std::convert::Infallible;
+
+	fn load(&self) -> Result<Vec<Message>, Self::Error> {
+		Ok(self.conversations.values().flatten().cloned().collect())
+	}
+
+	fn save(
+		&mut self, contact: &crate::id::User, messages: &[Message],
+	) -> Result<(), Self::Error> {
+		self.conversations.insert(contact.clone(), messages.to_vec());
+		Ok(())
+	}
+}
+
+/// The conversations of the logged in user
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Conversations {
+	conversations: HashMap<crate::id::User, Conversation>,
+	/// Contacts whose conversations have changed since they were last saved
+	unsaved: HashSet<crate::id::User>,
+}
+
+impl Conversations {
+	/// Loads the conversations from the store
+	///
+	/// # Errors
+	///
+	/// If the store fails to load the messages
+	pub fn load<S: ConversationStore>(store: &S) -> Result<Self, S::Error> {
+		let mut conversations = Self::default();
+		conversations.merge(store.load()?);
+		conversations.unsaved.clear();
+		Ok(conversations)
+	}
+
+	/// Saves the conversations that have changed since the last save
+	///
+	/// # Errors
+	///
+	/// If the store fails to save the messages, in which case the conversations
+	/// that weren't saved yet are attempted again on the next save.
+	pub fn save<S: ConversationStore>(
+		&mut self, store: &mut S,
+	) -> Result<(), S::Error> {
+		for contact in self.unsaved.clone() {
+			if let Some(conversation) = self.conversations.get(&contact) {
+				store.save(&contact, conversation.messages())?;
+			}
+			self.unsaved.remove(&contact);
+		}
+		Ok(())
+	}
+
+	/// Merges fetched or sent messages into the conversations.
+	///
+	/// Returns the contacts whose conversations changed.
+	pub fn merge(
+		&mut self, messages: impl IntoIterator<Item = Message>,
+	) -> Vec<crate::id::User> {
+		let mut changed = Vec::new();
+
+		for message in messages {
+			let contact = message.non_owner_id().clone();
+			let conversation = self
+				.conversations
+				.entry(contact.clone())
+				.or_insert_with(|| Conversation::new(contact.clone()));
+			if conversation.insert(message) && !changed.contains(&contact) {
+				changed.push(contact);
+			}
+		}
+
+		self.unsaved.extend(changed.iter().cloned());
+		changed
+	}
+
+	/// Locally marks the messages from the contact as read, returning their IDs
+	/// so that they can be marked as read with the API too.
+	pub fn mark_read(
+		&mut self, contact: &crate::id::User, read_time: OffsetDateTime,
+	) -> Vec<crate::id::Message> {
+		let Some(conversation) = self.conversations.get_mut(contact) else {
+			return Vec::new();
+		};
+		let read = conversation.mark_read(read_time);
+		if !read.is_empty() {
+			self.unsaved.insert(contact.clone());
+		}
+		read
+	}
+
+	#[must_use]
+	/// The conversation with the contact
+	pub fn conversation(
+		&self, contact: &crate::id::User,
+	) -> Option<&Conversation> {
+		self.conversations.get(contact)
+	}
+
+	#[must_use]
+	/// The conversations sorted by their newest message, newest first
+	pub fn by_latest(&self) -> Vec<&Conversation> {
+		let mut conversations: Vec<&Conversation> =
+			self.conversations.values().collect();
+		conversations.sort_by(|a, b| {
+			let latest = |conversation: &Conversation| {
+				conversation.last_message().map(|message| message.send_time)
+			};
+			latest(b)
+				.cmp(&latest(a))
+				.then_with(|| a.contact.as_ref().cmp(b.contact.as_ref()))
+		});
+		conversations
+	}
+
+	#[must_use]
+	/// The amount of unread messages in all of the conversations
+	pub fn unread_count(&self) -> usize {
+		self.conversations.values().map(Conversation::unread_count).sum()
+	}
+
+	#[must_use]
+	/// When the newest known message was last updated
+	pub fn last_update_time(&self) -> Option<OffsetDateTime> {
+		self
+			.conversations
+			.values()
+			.flat_map(Conversation::messages)
+			.map(|message| message.last_update_time)
+			.max()
+	}
+
+	#[must_use]
+	/// A query for the messages that were sent or updated after the newest known
+	/// one
+	pub fn next_query(&self) -> crate::query::Messages {
+		crate::query::Messages {
+			from_time: self.last_update_time(),
+			..crate::query::Messages::default()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ConversationStore, Conversations, MemoryStore};
+	use crate::model::Message;
+
+	fn message(
+		id: &str, sender: &str, recipient: &str, send_time: &str, read: bool,
+	) -> Message {
+		serde_json::from_value(serde_json::json!({
+			"id": id,
+			"ownerId": "U-owner",
+			"senderId": sender,
+			"recipientId": recipient,
+			"messageType": "Text",
+			"content": id,
+			"sendTime": send_time,
+			"lastUpdateTime": send_time,
+			"readTime": read.then_some(send_time),
+		}))
+		.expect("message to deserialize")
+	}
+
+	#[test]
+	fn merging() {
+		let mut conversations = Conversations::default();
+		let changed = conversations.merge([
+			message("MSG-b", "U-a", "U-owner", "2023-04-01T12:02:00Z", false),
+			message("MSG-a", "U-owner", "U-a", "2023-04-01T12:01:00Z", false),
+			message("MSG-c", "U-b", "U-owner", "2023-04-01T12:00:00Z", true),
+		]);
+		assert_eq!(changed.len(), 2);
+
+		let contact = crate::id::User::try_from("U-a").unwrap();
+		let conversation = conversations.conversation(&contact).unwrap();
+		let ids: Vec<&str> =
+			conversation.messages().iter().map(|m| m.id.as_ref()).collect();
+		assert_eq!(ids, ["MSG-a", "MSG-b"]);
+		assert_eq!(conversation.unread_count(), 1);
+		assert_eq!(conversations.unread_count(), 1);
+		assert_eq!(conversations.by_latest()[0].contact(), &contact);
+
+		// The same page again doesn't change anything
+		assert!(conversations
+			.merge([message(
+				"MSG-b",
+				"U-a",
+				"U-owner",
+				"2023-04-01T12:02:00Z",
+				false
+			)])
+			.is_empty());
+
+		let read =
+			conversations.mark_read(&contact, time::OffsetDateTime::UNIX_EPOCH);
+		assert_eq!(read.len(), 1);
+		assert_eq!(conversations.unread_count(), 0);
+		assert_eq!(
+			conversations
+				.next_query()
+				.from_time
+				.map(time::OffsetDateTime::unix_timestamp),
+			Some(1_680_350_520)
+		);
+	}
+
+	#[test]
+	fn store() {
+		let mut store = MemoryStore::default();
+		let mut conversations = Conversations::default();
+		conversations.merge([
+			message("MSG-a", "U-a", "U-owner", "2023-04-01T12:00:00Z", false),
+			message("MSG-b", "U-b", "U-owner", "2023-04-01T12:00:00Z", false),
+		]);
+		conversations.save(&mut store).unwrap();
+		assert_eq!(store.conversations.len(), 2);
+		assert_eq!(store.load().unwrap().len(), 2);
+
+		let loaded = Conversations::load(&store).unwrap();
+		assert_eq!(loaded.unread_count(), 2);
+		assert_eq!(loaded.by_latest().len(), 2);
+	}
+}
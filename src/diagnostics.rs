@@ -0,0 +1,651 @@
+//! Reporting the lenient fallbacks that were used while deserializing.
+//!
+//! The models are lenient by default, for example defaulting fields that are
+//! null or invalid and skipping array entries that fail to deserialize. That
+//! keeps things working when the API returns odd data, but also hides changes
+//! to the API.
+//!
+//! Deserializing with the functions of this module returns a [`Fallback`] for
+//! every time that such leniency was used, along with the path of the field.
+//!
+//! # Example usage
+//!
+//! ```
+//! use neos::diagnostics;
+//!
+//! let json = r#"{
+//! 	"username": "Neos",
+//! 	"userID": "U-Neos",
+//! 	"isPresent": true,
+//! 	"outputDevice": "Screen"
+//! }"#;
+//! let (user, fallbacks): (neos::model::SessionUser, _) =
+//! 	diagnostics::from_str(json).unwrap();
+//! assert_eq!(user.username, "Neos");
+//! assert!(fallbacks.is_empty());
+//! ```
+//!
+//! Fields inside of flattened fields, like the ones with the `extra_fields`
+//! feature, are reported with the path of the closest parent that's not
+//! flattened.
+//!
+//! When the fallbacks aren't being collected, the lenient adapters of this
+//! module just forward to the ones of [`serde_with`], so normal deserializing
+//! doesn't pay for the diagnostics. While collecting, lenient values are
+//! buffered as [`serde_json::Value`]s, so that the error can be reported.
+
+use std::{cell::RefCell, fmt::Display, marker::PhantomData};
+
+use serde::{
+	de::{
+		DeserializeSeed,
+		Deserializer,
+		IntoDeserializer,
+		MapAccess,
+		SeqAccess,
+		Visitor,
+	},
+	Deserialize,
+	Serializer,
+};
+use serde_with::{de::DeserializeAsWrap, DeserializeAs, SerializeAs};
+
+/// What kind of leniency was used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FallbackKind {
+	/// The value was null, so the default was used instead
+	NullDefaulted,
+	/// The value failed to deserialize, so the default was used instead
+	ErrorDefaulted,
+	/// The array entry failed to deserialize, so it was skipped
+	Skipped,
+}
+
+/// A single time that leniency was used while deserializing
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fallback {
+	/// The path of the field, such as `userStatus.activeSessions[0].name`.
+	///
+	/// Empty if the root value itself fell back.
+	pub path: String,
+	/// What kind of leniency was used
+	pub kind: FallbackKind,
+	/// Why the fallback was needed, such as the deserialization error
+	pub reason: String,
+}
+
+/// Deserializes the value, also returning the fallbacks that were used.
+///
+/// # Errors
+///
+/// If deserializing fails even with the leniency
+pub fn deserialize<'de, T, D>(
+	deserializer: D,
+) -> Result<(T, Vec<Fallback>), D::Error>
+where
+	T: Deserialize<'de>,
+	D: Deserializer<'de>,
+{
+	let previous = COLLECTOR.with(|c| c.replace(Some(Collector::default())));
+	let guard = CollectorGuard(previous);
+	let result = T::deserialize(Tracked(deserializer));
+	let collector = COLLECTOR.with(RefCell::take);
+	drop(guard);
+
+	result
+		.map(|value| (value, collector.map(|c| c.fallbacks).unwrap_or_default()))
+}
+
+/// Deserializes the value from a JSON string, also returning the fallbacks
+/// that were used.
+///
+/// # Errors
+///
+/// If the JSON is invalid or deserializing fails even with the leniency
+pub fn from_str<'a, T: Deserialize<'a>>(
+	json: &'a str,
+) -> serde_json::Result<(T, Vec<Fallback>)> {
+	let mut deserializer = serde_json::Deserializer::from_str(json);
+	let result = deserialize(&mut deserializer)?;
+	deserializer.end()?;
+	Ok(result)
+}
+
+/// Deserializes the value from JSON bytes, also returning the fallbacks that
+/// were used.
+///
+/// # Errors
+///
+/// If the JSON is invalid or deserializing fails even with the leniency
+pub fn from_slice<'a, T: Deserialize<'a>>(
+	json: &'a [u8],
+) -> serde_json::Result<(T, Vec<Fallback>)> {
+	let mut deserializer = serde_json::Deserializer::from_slice(json);
+	let result = deserialize(&mut deserializer)?;
+	deserializer.end()?;
+	Ok(result)
+}
+
+/// Deserializes the value from a JSON value, also returning the fallbacks that
+/// were used.
+///
+/// # Errors
+///
+/// If deserializing fails even with the leniency
+pub fn from_value<T: serde::de::DeserializeOwned>(
+	json: serde_json::Value,
+) -> serde_json::Result<(T, Vec<Fallback>)> {
+	deserialize(json)
+}
+
+/// Like [`serde_with::DefaultOnError`], but reports the fallbacks.
+pub struct DefaultOnError<U = serde_with::Same>(PhantomData<U>);
+
+impl<'de, T, U> DeserializeAs<'de, T> for DefaultOnError<U>
+where
+	T: Default,
+	U: for<'a> DeserializeAs<'a, T>,
+{
+	fn deserialize_as<D>(deserializer: D) -> Result<T, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		if !is_collecting() {
+			return serde_with::DefaultOnError::<U>::deserialize_as(deserializer);
+		}
+
+		let value = match serde_json::Value::deserialize(deserializer) {
+			Ok(value) => value,
+			Err(err) => {
+				report(FallbackKind::ErrorDefaulted, err);
+				return Ok(T::default());
+			}
+		};
+
+		Ok(DeserializeAsWrap::<T, U>::deserialize(Tracked(value)).map_or_else(
+			|err| {
+				report(FallbackKind::ErrorDefaulted, err);
+				T::default()
+			},
+			DeserializeAsWrap::into_inner,
+		))
+	}
+}
+
+impl<T, U: SerializeAs<T>> SerializeAs<T> for DefaultOnError<U> {
+	fn serialize_as<S: Serializer>(
+		source: &T, serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		U::serialize_as(source, serializer)
+	}
+}
+
+/// Like [`serde_with::DefaultOnNull`], but reports the fallbacks.
+pub struct DefaultOnNull<U = serde_with::Same>(PhantomData<U>);
+
+impl<'de, T, U> DeserializeAs<'de, T> for DefaultOnNull<U>
+where
+	T: Default,
+	U: DeserializeAs<'de, T>,
+{
+	fn deserialize_as<D>(deserializer: D) -> Result<T, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		Ok(
+			Option::<DeserializeAsWrap<T, U>>::deserialize(deserializer)?
+				.map_or_else(
+					|| {
+						report(FallbackKind::NullDefaulted, "null");
+						T::default()
+					},
+					DeserializeAsWrap::into_inner,
+				),
+		)
+	}
+}
+
+impl<T, U: SerializeAs<T>> SerializeAs<T> for DefaultOnNull<U> {
+	fn serialize_as<S: Serializer>(
+		source: &T, serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		serializer
+			.serialize_some(&serde_with::ser::SerializeAsWrap::<T, U>::new(source))
+	}
+}
+
+/// Like [`serde_with::VecSkipError`], but reports the fallbacks.
+pub struct VecSkipError<U>(PhantomData<U>);
+
+impl<'de, T, U> DeserializeAs<'de, Vec<T>> for VecSkipError<U>
+where
+	U: for<'a> DeserializeAs<'a, T>,
+{
+	fn deserialize_as<D>(deserializer: D) -> Result<Vec<T>, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		if !is_collecting() {
+			return serde_with::VecSkipError::<U>::deserialize_as(deserializer);
+		}
+
+		let values = Vec::<serde_json::Value>::deserialize(deserializer)?;
+
+		Ok(
+			values
+				.into_iter()
+				.enumerate()
+				.filter_map(|(index, value)| {
+					with_segment(
+						|| Segment::Index(index),
+						|| match DeserializeAsWrap::<T, U>::deserialize(Tracked(value)) {
+							Ok(value) => Some(value.into_inner()),
+							Err(err) => {
+								report(FallbackKind::Skipped, err);
+								None
+							}
+						},
+					)
+				})
+				.collect(),
+		)
+	}
+}
+
+impl<T, U: SerializeAs<T>> SerializeAs<Vec<T>> for VecSkipError<U> {
+	fn serialize_as<S: Serializer>(
+		source: &Vec<T>, serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		Vec::<U>::serialize_as(source, serializer)
+	}
+}
+
+/// If fallbacks are being collected on this thread
+fn is_collecting() -> bool {
+	COLLECTOR.with(|collector| collector.borrow().is_some())
+}
+
+/// Reports a fallback at the current path, if fallbacks are being collected
+pub(crate) fn report(kind: FallbackKind, reason: impl Display) {
+	COLLECTOR.with(|collector| {
+		if let Some(collector) = collector.borrow_mut().as_mut() {
+			let path = collector.path();
+			collector.fallbacks.push(Fallback {
+				path,
+				kind,
+				reason: reason.to_string(),
+			});
+		}
+	});
+}
+
+thread_local! {
+	static COLLECTOR: RefCell<Option<Collector>> = const { RefCell::new(None) };
+}
+
+enum Segment {
+	Key(String),
+	Index(usize),
+}
+
+#[derive(Default)]
+struct Collector {
+	path: Vec<Segment>,
+	fallbacks: Vec<Fallback>,
+}
+
+impl Collector {
+	fn path(&self) -> String {
+		let mut path = String::new();
+		for segment in &self.path {
+			match segment {
+				Segment::Key(key) => {
+					if !path.is_empty() {
+						path.push('.');
+					}
+					path.push_str(key);
+				}
+				Segment::Index(index) => {
+					path.push('[');
+					path.push_str(&index.to_string());
+					path.push(']');
+				}
+			}
+		}
+		path
+	}
+}
+
+/// Restores the previous collector, even if deserializing panics
+struct CollectorGuard(Option<Collector>);
+
+impl Drop for CollectorGuard {
+	fn drop(&mut self) {
+		let previous = self.0.take();
+		COLLECTOR.with(|c| *c.borrow_mut() = previous);
+	}
+}
+
+/// Runs the function with the segment added to the current path
+fn with_segment<R>(
+	segment: impl FnOnce() -> Segment, f: impl FnOnce() -> R,
+) -> R {
+	let pushed = COLLECTOR.with(|collector| {
+		collector.borrow_mut().as_mut().map(|c| c.path.push(segment())).is_some()
+	});
+	let result = f();
+	if pushed {
+		COLLECTOR.with(|collector| {
+			if let Some(c) = collector.borrow_mut().as_mut() {
+				c.path.pop();
+			}
+		});
+	}
+	result
+}
+
+/// A deserializer that keeps track of the current path
+struct Tracked<D>(D);
+
+macro_rules! forward_deserialize {
+	($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+		$(
+			fn $method<V: Visitor<'de>>(
+				self, $($arg: $ty,)* visitor: V,
+			) -> Result<V::Value, Self::Error> {
+				self.0.$method($($arg,)* TrackedVisitor(visitor))
+			}
+		)*
+	};
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Tracked<D> {
+	type Error = D::Error;
+
+	forward_deserialize!(
+		deserialize_any(),
+		deserialize_bool(),
+		deserialize_i8(),
+		deserialize_i16(),
+		deserialize_i32(),
+		deserialize_i64(),
+		deserialize_i128(),
+		deserialize_u8(),
+		deserialize_u16(),
+		deserialize_u32(),
+		deserialize_u64(),
+		deserialize_u128(),
+		deserialize_f32(),
+		deserialize_f64(),
+		deserialize_char(),
+		deserialize_str(),
+		deserialize_string(),
+		deserialize_bytes(),
+		deserialize_byte_buf(),
+		deserialize_option(),
+		deserialize_unit(),
+		deserialize_unit_struct(name: &'static str),
+		deserialize_newtype_struct(name: &'static str),
+		deserialize_seq(),
+		deserialize_tuple(len: usize),
+		deserialize_tuple_struct(name: &'static str, len: usize),
+		deserialize_map(),
+		deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+		deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+		deserialize_identifier(),
+		deserialize_ignored_any(),
+	);
+
+	fn is_human_readable(&self) -> bool { self.0.is_human_readable() }
+}
+
+struct TrackedVisitor<V>(V);
+
+macro_rules! forward_visit {
+	($($method:ident($ty:ty)),* $(,)?) => {
+		$(
+			fn $method<E: serde::de::Error>(self, v: $ty) -> Result<Self::Value, E> {
+				self.0.$method(v)
+			}
+		)*
+	};
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for TrackedVisitor<V> {
+	type Value = V::Value;
+
+	forward_visit!(
+		visit_bool(bool),
+		visit_i8(i8),
+		visit_i16(i16),
+		visit_i32(i32),
+		visit_i64(i64),
+		visit_i128(i128),
+		visit_u8(u8),
+		visit_u16(u16),
+		visit_u32(u32),
+		visit_u64(u64),
+		visit_u128(u128),
+		visit_f32(f32),
+		visit_f64(f64),
+		visit_char(char),
+		visit_str(&str),
+		visit_borrowed_str(&'de str),
+		visit_string(String),
+		visit_bytes(&[u8]),
+		visit_borrowed_bytes(&'de [u8]),
+		visit_byte_buf(Vec<u8>),
+	);
+
+	fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+		self.0.expecting(formatter)
+	}
+
+	fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+		self.0.visit_none()
+	}
+
+	fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+		self.0.visit_unit()
+	}
+
+	fn visit_some<D: Deserializer<'de>>(
+		self, deserializer: D,
+	) -> Result<Self::Value, D::Error> {
+		self.0.visit_some(Tracked(deserializer))
+	}
+
+	fn visit_newtype_struct<D: Deserializer<'de>>(
+		self, deserializer: D,
+	) -> Result<Self::Value, D::Error> {
+		self.0.visit_newtype_struct(Tracked(deserializer))
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(
+		self, seq: A,
+	) -> Result<Self::Value, A::Error> {
+		self.0.visit_seq(TrackedSeq { seq, index: 0 })
+	}
+
+	fn visit_map<A: MapAccess<'de>>(
+		self, map: A,
+	) -> Result<Self::Value, A::Error> {
+		self.0.visit_map(TrackedMap { map, key: None })
+	}
+
+	fn visit_enum<A: serde::de::EnumAccess<'de>>(
+		self, data: A,
+	) -> Result<Self::Value, A::Error> {
+		self.0.visit_enum(data)
+	}
+}
+
+struct TrackedSeed<S>(S);
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for TrackedSeed<S> {
+	type Value = S::Value;
+
+	fn deserialize<D: Deserializer<'de>>(
+		self, deserializer: D,
+	) -> Result<Self::Value, D::Error> {
+		self.0.deserialize(Tracked(deserializer))
+	}
+}
+
+struct TrackedSeq<A> {
+	seq: A,
+	index: usize,
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for TrackedSeq<A> {
+	type Error = A::Error;
+
+	fn next_element_seed<T: DeserializeSeed<'de>>(
+		&mut self, seed: T,
+	) -> Result<Option<T::Value>, Self::Error> {
+		let index = self.index;
+		self.index += 1;
+		with_segment(
+			|| Segment::Index(index),
+			|| self.seq.next_element_seed(TrackedSeed(seed)),
+		)
+	}
+
+	fn size_hint(&self) -> Option<usize> { self.seq.size_hint() }
+}
+
+struct TrackedMap<A> {
+	map: A,
+	key: Option<String>,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for TrackedMap<A> {
+	type Error = A::Error;
+
+	fn next_key_seed<K: DeserializeSeed<'de>>(
+		&mut self, seed: K,
+	) -> Result<Option<K::Value>, Self::Error> {
+		// JSON keys are always strings, so they can be read to keep for the path
+		let Some(key) = self.map.next_key::<String>()? else {
+			return Ok(None);
+		};
+		let value = seed.deserialize(key.as_str().into_deserializer())?;
+		self.key = Some(key);
+		Ok(Some(value))
+	}
+
+	fn next_value_seed<V: DeserializeSeed<'de>>(
+		&mut self, seed: V,
+	) -> Result<V::Value, Self::Error> {
+		let key = self.key.take().unwrap_or_default();
+		with_segment(
+			|| Segment::Key(key),
+			|| self.map.next_value_seed(TrackedSeed(seed)),
+		)
+	}
+
+	fn size_hint(&self) -> Option<usize> { self.map.size_hint() }
+}
+
+#[cfg(test)]
+mod tests {
+	use serde::Deserialize;
+
+	use super::FallbackKind;
+
+	#[serde_with::serde_as]
+	#[derive(Debug, Deserialize)]
+	struct Inner {
+		#[serde_as(deserialize_as = "super::DefaultOnError")]
+		#[serde(default)]
+		value: u8,
+	}
+
+	#[serde_with::serde_as]
+	#[derive(Debug, Deserialize)]
+	struct Outer {
+		nested: Inner,
+		list: Vec<Inner>,
+		#[serde_as(deserialize_as = "super::VecSkipError<serde_with::Same>")]
+		lenient: Vec<Inner>,
+	}
+
+	fn has(
+		fallbacks: &[super::Fallback], path: &str, kind: FallbackKind,
+	) -> bool {
+		fallbacks
+			.iter()
+			.any(|fallback| fallback.path == path && fallback.kind == kind)
+	}
+
+	#[test]
+	fn reports_paths() {
+		let mut json = crate::model::fixtures::friend();
+		json["userStatus"]["onlineStatus"] = "Online".into();
+		json["userStatus"]["lastStatusChange"] = "not a time".into();
+		// Null entries are only skipped without the `debug` feature
+		if !cfg!(feature = "debug") {
+			json["userStatus"]["activeSessions"] = serde_json::json!([null]);
+		}
+		let (_, fallbacks): (crate::model::Friend, _) =
+			super::from_value(json).expect("deserializing to work");
+
+		assert!(has(
+			&fallbacks,
+			"userStatus.lastStatusChange",
+			FallbackKind::ErrorDefaulted
+		));
+		#[cfg(not(feature = "debug"))]
+		assert!(has(
+			&fallbacks,
+			"userStatus.activeSessions[0]",
+			FallbackKind::Skipped
+		));
+		#[cfg(not(feature = "debug"))]
+		assert_eq!(fallbacks.len(), 2, "{fallbacks:?}");
+		#[cfg(feature = "debug")]
+		assert_eq!(fallbacks.len(), 1, "{fallbacks:?}");
+	}
+
+	#[test]
+	fn reports_nested_and_indexed_paths() {
+		let json = serde_json::json!({
+			"nested": { "value": "not a number" },
+			"list": [{ "value": 1 }, { "value": -1 }],
+			"lenient": [null, { "value": 1 }, { "value": 256 }],
+		});
+		let (outer, fallbacks): (Outer, _) =
+			super::from_value(json).expect("deserializing to work");
+
+		assert_eq!(outer.nested.value, 0);
+		assert_eq!(outer.list.len(), 2);
+		assert_eq!(outer.lenient.len(), 2);
+		assert!(has(&fallbacks, "nested.value", FallbackKind::ErrorDefaulted));
+		assert!(has(&fallbacks, "list[1].value", FallbackKind::ErrorDefaulted));
+		assert!(has(&fallbacks, "lenient[0]", FallbackKind::Skipped));
+		assert!(has(&fallbacks, "lenient[2].value", FallbackKind::ErrorDefaulted));
+		assert_eq!(fallbacks.len(), 4, "{fallbacks:?}");
+	}
+
+	#[test]
+	fn lenient_without_collecting() {
+		let json = serde_json::json!({
+			"nested": { "value": "not a number" },
+			"list": [],
+			"lenient": [null, { "value": 1 }],
+		});
+		let outer: Outer =
+			serde_json::from_value(json).expect("deserializing to work");
+		assert_eq!(outer.nested.value, 0);
+		assert_eq!(outer.lenient.len(), 1);
+	}
+
+	#[test]
+	fn nothing_collected_outside() {
+		super::report(FallbackKind::Skipped, "not collected");
+		let (value, fallbacks): (Vec<u8>, _) =
+			super::from_str("[1, 2]").expect("deserializing to work");
+		assert_eq!(value, [1, 2]);
+		assert!(fallbacks.is_empty());
+	}
+}
@@ -13,7 +13,7 @@
 //! correct ID prefix.
 //!
 //! Note that the IDs seem to be handled as case-sensitive, so any normalized
-//! versions are represented as strings instead of IDs.
+//! versions are represented with the [`Normalized`] wrapper instead.
 
 use serde::de::{self, Deserializer, Visitor};
 use serde::{Deserialize, Serialize};
@@ -23,6 +23,14 @@ macro_rules! add_id {
 		$(#[$meta:meta])*
 		$name:ident,
 		$prefix:expr
+	) => {
+		add_id!($(#[$meta])* $name, $prefix, validate_characters);
+	};
+	(
+		$(#[$meta:meta])*
+		$name:ident,
+		$prefix:expr,
+		$validate:path
 	) => {
 		#[doc = concat!("An ID of a Neos ", stringify!($name), "(`", $prefix, "{id}`)")]
 		///
@@ -39,14 +47,69 @@ macro_rules! add_id {
 		$(#[$meta])*
 		pub struct $name(String);
 
+		impl $name {
+			/// The prefix that the ID starts with
+			pub const PREFIX: &'static str = $prefix;
+
+			#[must_use]
+			/// The part of the ID after the prefix
+			pub fn without_prefix(&self) -> &str {
+				&self.0[$prefix.len()..]
+			}
+
+			#[must_use]
+			/// The part of the ID after the prefix as an UUID, if it is one
+			pub fn uuid(&self) -> Option<uuid::Uuid> {
+				uuid::Uuid::try_parse(self.without_prefix()).ok()
+			}
+
+			#[must_use]
+			/// The normalized form of the ID
+			pub fn normalized(&self) -> Normalized<Self> {
+				Normalized::new(self)
+			}
+
+			/// Checks that the ID only has characters that are allowed in it.
+			///
+			/// The part after the prefix can't be empty, or contain whitespace,
+			/// control characters or ones that have a special meaning in URLs.
+			/// The only exception are the names of custom sessions, which can
+			/// contain spaces.
+			///
+			/// # Errors
+			///
+			/// If the ID contains characters that aren't allowed
+			pub fn validate(&self) -> Result<(), &'static str> {
+				$validate(self.without_prefix())
+			}
+		}
+
 		impl AsRef<str> for $name {
 			/// Extracts a string slice containing the entire inner String.
-			#[must_use]
 			fn as_ref(&self) -> &str {
 				&self.0
 			}
 		}
 
+		impl std::borrow::Borrow<str> for $name {
+			fn borrow(&self) -> &str {
+				&self.0
+			}
+		}
+
+		impl std::fmt::Display for $name {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				f.write_str(&self.0)
+			}
+		}
+
+		impl std::str::FromStr for $name {
+			type Err = &'static str;
+			fn from_str(v: &str) -> Result<Self, Self::Err> {
+				Self::try_from(v.to_owned())
+			}
+		}
+
 		impl TryFrom<String> for $name {
 			type Error = &'static str;
 			fn try_from(v: String) -> Result<Self, Self::Error> {
@@ -71,9 +134,14 @@ macro_rules! add_id {
 			}
 		}
 
-		impl From<$name> for Any {
-			fn from(id: $name) -> Any {
-				Any::$name(id)
+		impl TryFrom<Any> for $name {
+			type Error = Any;
+			/// Gives back the ID as the error if it's of another type
+			fn try_from(id: Any) -> Result<Self, Self::Error> {
+				match id {
+					Any::$name(id) => Ok(id),
+					other => Err(other),
+				}
 			}
 		}
 
@@ -85,7 +153,7 @@ macro_rules! add_id {
 			{
 				struct IdVisitor;
 
-				impl<'de> Visitor<'de> for IdVisitor {
+				impl Visitor<'_> for IdVisitor {
 					type Value = $name;
 
 					fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -115,68 +183,525 @@ macro_rules! add_id {
 
 add_id!(User, "U-");
 add_id!(Group, "G-");
-add_id!(Session, "S-");
+add_id!(Session, "S-", validate_session);
 add_id!(Record, "R-");
 add_id!(Machine, "M-");
+add_id!(Message, "MSG-");
+add_id!(
+	///
+	/// The prefix hasn't been confirmed against the API yet, so
+	/// [`crate::model::Submission::id`] is kept as a string, see
+	/// [`crate::model::Submission::typed_id`].
+	Submission,
+	"SUB-"
+);
+add_id!(Transaction, "T-");
 
-impl Session {
-	#[must_use]
-	/// If the session is hosted by an user.
-	pub fn is_custom_user(&self) -> bool { self.0.starts_with("S-U-") }
-}
-
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
-#[serde(untagged)]
-/// Any of the Neos IDs
+/// The normalized form of an ID or username, to compare them like Neos does.
+///
+/// Neos normalizes them to lowercase, so `U-Foo` and `u-foo` are the same.
+/// The wrapper can be borrowed as a `str`, so maps can be searched with
+/// normalized strings.
 ///
 /// # Example usage
 ///
 /// ```
-/// let id1 = neos::id::User::try_from("U-totally-legit-id").unwrap();
-/// let id1: neos::id::Any = id1.into();
-/// let id2 = neos::id::Record::try_from("R-totally-legit-id").unwrap();
-/// let id2: neos::id::Any = id2.into();
-/// assert!(id1 != id2);
-/// ```
-pub enum Any {
-	/// An user ID
-	User(User),
-	/// A group ID
-	Group(Group),
-	/// A session ID
-	Session(Session),
-	/// A record ID
-	Record(Record),
-	/// A machine ID
-	Machine(Machine),
-}
-
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
-#[serde(untagged)]
-/// Neos IDs that can own records for example
+/// use std::collections::HashMap;
 ///
-/// # Example usage
+/// use neos::id::{Normalized, User};
 ///
+/// let mut directory: HashMap<Normalized<User>, &str> = HashMap::new();
+/// let id = User::try_from("U-Foo").unwrap();
+/// directory.insert(id.normalized(), "Foo");
+///
+/// let other = User::try_from("U-FOO").unwrap();
+/// assert_eq!(directory.get(&other.normalized()), Some(&"Foo"));
+/// assert_eq!(
+/// 	directory.get(Normalized::<User>::normalize("U-fOO").as_str()),
+/// 	Some(&"Foo")
+/// );
 /// ```
-/// let id1 = neos::id::User::try_from("U-totally-legit-id").unwrap();
-/// let id1: neos::id::Owner = id1.into();
-/// let id2 = neos::id::Group::try_from("G-totally-legit-id").unwrap();
-/// let id2: neos::id::Owner = id2.into();
-/// assert!(id1 != id2);
-/// ```
-pub enum Owner {
-	/// An user ID
-	User(User),
-	/// A group ID
-	Group(Group),
-	/// A machine ID
-	Machine(Machine),
+pub struct Normalized<T> {
+	normalized: String,
+	phantom: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T> Normalized<T> {
+	#[must_use]
+	/// Normalizes the ID or username
+	pub fn new(value: &T) -> Self
+	where
+		T: AsRef<str>,
+	{
+		Self::normalize(value.as_ref())
+	}
+
+	#[must_use]
+	/// Normalizes a string form of the ID or username
+	pub fn normalize(value: &str) -> Self {
+		Self { normalized: value.to_lowercase(), phantom: std::marker::PhantomData }
+	}
+
+	#[must_use]
+	/// The normalized string
+	pub fn as_str(&self) -> &str { &self.normalized }
+
+	#[must_use]
+	/// If the ID or username is the same as this one when normalized
+	pub fn matches(&self, value: &T) -> bool
+	where
+		T: AsRef<str>,
+	{
+		value.as_ref().to_lowercase() == self.normalized
+	}
+}
+
+impl<T> Clone for Normalized<T> {
+	fn clone(&self) -> Self { Self::normalize(&self.normalized) }
+}
+
+impl<T> std::fmt::Debug for Normalized<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_tuple("Normalized").field(&self.normalized).finish()
+	}
+}
+
+impl<T> PartialEq for Normalized<T> {
+	fn eq(&self, other: &Self) -> bool { self.normalized == other.normalized }
+}
+
+impl<T> Eq for Normalized<T> {}
+
+impl<T> std::hash::Hash for Normalized<T> {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		self.normalized.hash(state);
+	}
+}
+
+impl<T> AsRef<str> for Normalized<T> {
+	fn as_ref(&self) -> &str { &self.normalized }
+}
+
+impl<T> std::borrow::Borrow<str> for Normalized<T> {
+	fn borrow(&self) -> &str { &self.normalized }
+}
+
+impl<T> std::fmt::Display for Normalized<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&self.normalized)
+	}
+}
+
+impl<T> Serialize for Normalized<T> {
+	fn serialize<S: serde::Serializer>(
+		&self, serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.normalized)
+	}
+}
+
+/// The deserializer normalizes the string, in case it wasn't already.
+impl<'de, T> Deserialize<'de> for Normalized<T> {
+	fn deserialize<D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<Self, D::Error> {
+		String::deserialize(deserializer).map(|value| Self::normalize(&value))
+	}
+}
+
+/// Characters that have a special meaning in URLs
+const URL_SPECIAL_CHARS: &[char] = &['/', '\\', '?', '#', '%', '"', '<', '>'];
+
+fn validate_characters(id: &str) -> Result<(), &'static str> {
+	if id.is_empty() {
+		return Err("should have characters after the prefix");
+	}
+	if id.chars().any(|c| {
+		c.is_whitespace() || c.is_control() || URL_SPECIAL_CHARS.contains(&c)
+	}) {
+		return Err(
+			"shouldn't contain whitespace, control characters or any of `/\\?#%\"<>`",
+		);
+	}
+	Ok(())
+}
+
+/// Like [`validate_characters`], but allows spaces in the name of custom
+/// sessions like `S-U-{username}:{name}`, as Neos allows them in the names
+fn validate_session(id: &str) -> Result<(), &'static str> {
+	let Some((host, name)) =
+		id.strip_prefix("U-").and_then(|id| id.split_once(':'))
+	else {
+		return validate_characters(id);
+	};
+	validate_characters(host)?;
+	if name.trim().is_empty() {
+		return Err("should have a name after the `:`");
+	}
+	if name.starts_with(' ') || name.ends_with(' ') {
+		return Err("the name shouldn't start or end with spaces");
+	}
+	name
+		.split(' ')
+		.filter(|part| !part.is_empty())
+		.try_for_each(validate_characters)
+}
+
+impl Session {
+	#[must_use]
+	/// If the session is hosted by an user.
+	pub fn is_custom_user(&self) -> bool { self.0.starts_with("S-U-") }
+
+	#[must_use]
+	/// The user that hosts the session, for custom sessions like
+	/// `S-U-{username}:{name}`
+	pub fn host_user(&self) -> Option<User> {
+		let (user, _) = self.without_prefix().split_once(':')?;
+		User::try_from(user.to_owned()).ok()
+	}
+
+	#[must_use]
+	/// The name part of custom sessions like `S-U-{username}:{name}`
+	pub fn custom_name(&self) -> Option<&str> {
+		if !self.is_custom_user() {
+			return None;
+		}
+		self.without_prefix().split_once(':').map(|(_, name)| name)
+	}
+}
+
+impl Message {
+	#[cfg(feature = "rand_util")]
+	#[must_use]
+	/// Generates a new random message ID, with an UUID like Neos does.
+	///
+	/// Only cryptographically safe with the `secure_rand` feature.
+	pub fn random() -> Self {
+		Self(format!("MSG-{}", crate::util::random_uuid().hyphenated()))
+	}
+}
+
+impl Record {
+	#[cfg(feature = "rand_util")]
+	#[must_use]
+	/// Generates a new random record ID, with an UUID like Neos does.
+	///
+	/// Only cryptographically safe with the `secure_rand` feature.
+	pub fn random() -> Self {
+		Self(format!("R-{}", crate::util::random_uuid().hyphenated()))
+	}
+}
+
+macro_rules! add_id_enum {
+	(
+		$(#[$meta:meta])*
+		$name:ident {
+			$(
+				#[doc = $doc:expr]
+				$variant:ident: $prefix:expr
+			),* $(,)?
+		}
+	) => {
+		#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+		#[serde(untagged)]
+		$(#[$meta])*
+		pub enum $name {
+			$(
+				#[doc = $doc]
+				$variant($variant),
+			)*
+		}
+
+		impl $name {
+			/// The prefixes of the IDs that this can be
+			pub const PREFIXES: &'static [&'static str] = &[$($prefix),*];
+		}
+
+		impl AsRef<str> for $name {
+			fn as_ref(&self) -> &str {
+				match self {
+					$(Self::$variant(id) => id.as_ref(),)*
+				}
+			}
+		}
+
+		impl std::fmt::Display for $name {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				f.write_str(self.as_ref())
+			}
+		}
+
+		impl std::str::FromStr for $name {
+			type Err = &'static str;
+			fn from_str(v: &str) -> Result<Self, Self::Err> {
+				Self::try_from(v.to_owned())
+			}
+		}
+
+		/// Picks the type of the ID based on its prefix
+		impl TryFrom<String> for $name {
+			type Error = &'static str;
+			fn try_from(v: String) -> Result<Self, Self::Error> {
+				$(
+					if v.starts_with($prefix) {
+						return Ok(Self::$variant($variant(v)));
+					}
+				)*
+				Err(concat!("should start with one of" $(, " `", $prefix, "`")*))
+			}
+		}
+
+		/// For easier scripting, should use String otherwise.
+		impl TryFrom<&'static str> for $name {
+			type Error = &'static str;
+			fn try_from(v: &'static str) -> Result<Self, Self::Error> {
+				Self::try_from(v.to_owned())
+			}
+		}
+
+		impl From<$name> for String {
+			fn from(id: $name) -> String {
+				match id {
+					$($name::$variant(id) => id.0,)*
+				}
+			}
+		}
+
+		$(
+			impl From<$variant> for $name {
+				fn from(id: $variant) -> Self {
+					Self::$variant(id)
+				}
+			}
+		)*
+
+		/// The deserializer will give an error listing the accepted prefixes if
+		/// the inner String doesn't start with any of them.
+		impl<'de> serde::de::Deserialize<'de> for $name {
+			fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+			where
+				D: Deserializer<'de>,
+			{
+				struct IdVisitor;
+
+				impl Visitor<'_> for IdVisitor {
+					type Value = $name;
+
+					fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+						formatter.write_str(concat!(
+							"a string, ", stringify!($name), " ID that must start with one of"
+							$(, " `", $prefix, "`")*
+						))
+					}
+
+					fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+					where
+						E: de::Error,
+					{
+						$name::try_from(v.to_owned()).map_err(|_| {
+							de::Error::invalid_value(
+								serde::de::Unexpected::Str(v),
+								&concat!("start with one of" $(, " `", $prefix, "`")*),
+							)
+						})
+					}
+				}
+
+				deserializer.deserialize_str(IdVisitor)
+			}
+		}
+	};
+}
+
+add_id_enum!(
+	/// Any of the Neos IDs
+	///
+	/// # Example usage
+	///
+	/// ```
+	/// let id1 = neos::id::User::try_from("U-totally-legit-id").unwrap();
+	/// let id1: neos::id::Any = id1.into();
+	/// let id2: neos::id::Any = "R-totally-legit-id".parse().unwrap();
+	/// assert!(id1 != id2);
+	/// assert!(matches!(id2, neos::id::Any::Record(_)));
+	/// ```
+	Any {
+		/// An user ID
+		User: "U-",
+		/// A group ID
+		Group: "G-",
+		/// A session ID
+		Session: "S-",
+		/// A record ID
+		Record: "R-",
+		/// A machine ID
+		Machine: "M-",
+		/// A message ID
+		Message: "MSG-",
+		/// A submission ID
+		Submission: "SUB-",
+		/// A credit transaction ID
+		Transaction: "T-",
+	}
+);
+
+add_id_enum!(
+	/// Neos IDs that can own records for example
+	///
+	/// # Example usage
+	///
+	/// ```
+	/// let id1 = neos::id::User::try_from("U-totally-legit-id").unwrap();
+	/// let id1: neos::id::Owner = id1.into();
+	/// let id2: neos::id::Owner = "G-totally-legit-id".parse().unwrap();
+	/// assert!(id1 != id2);
+	/// assert!("R-totally-legit-id".parse::<neos::id::Owner>().is_err());
+	/// ```
+	Owner {
+		/// An user ID
+		User: "U-",
+		/// A group ID
+		Group: "G-",
+		/// A machine ID
+		Machine: "M-",
+	}
+);
+
+impl From<Owner> for Any {
+	fn from(id: Owner) -> Self {
+		match id {
+			Owner::User(id) => Self::User(id),
+			Owner::Group(id) => Self::Group(id),
+			Owner::Machine(id) => Self::Machine(id),
+		}
+	}
 }
 
-impl From<User> for Owner {
-	fn from(user: User) -> Self { Self::User(user) }
+impl TryFrom<Any> for Owner {
+	type Error = Any;
+
+	/// Gives back the ID as the error if it can't be an owner
+	fn try_from(id: Any) -> Result<Self, Self::Error> {
+		match id {
+			Any::User(id) => Ok(Self::User(id)),
+			Any::Group(id) => Ok(Self::Group(id)),
+			Any::Machine(id) => Ok(Self::Machine(id)),
+			other => Err(other),
+		}
+	}
 }
 
-impl From<Group> for Owner {
-	fn from(group: Group) -> Self { Self::Group(group) }
+#[cfg(test)]
+mod tests {
+	use super::{
+		Any,
+		Group,
+		Machine,
+		Message,
+		Normalized,
+		Owner,
+		Record,
+		Session,
+		Submission,
+		Transaction,
+		User,
+	};
+
+	#[test]
+	fn prefixes() {
+		assert!(Message::try_from("MSG-abc").is_ok());
+		assert!(Message::try_from("M-abc").is_err());
+		assert!(Session::try_from("SUB-abc").is_err());
+	}
+
+	#[test]
+	fn internals() {
+		let session: Session = "S-U-Neos:My World".parse().unwrap();
+		assert_eq!(session.host_user(), Some(User::try_from("U-Neos").unwrap()));
+		assert_eq!(session.custom_name(), Some("My World"));
+		assert!(session.validate().is_ok());
+		assert!(Session::try_from("S-U-Neos:My\tWorld")
+			.unwrap()
+			.validate()
+			.is_err());
+		assert!(Session::try_from("S-U-Neos: ").unwrap().validate().is_err());
+		assert!(Session::try_from("S-U-Ne os:World").unwrap().validate().is_err());
+		assert!(Session::try_from("S-My World").unwrap().validate().is_err());
+		assert_eq!(session.normalized().as_str(), "s-u-neos:my world");
+
+		let record: Record =
+			"R-936da01f-9abd-4d9d-80c7-02af85c822a8".parse().unwrap();
+		assert!(record.uuid().is_some());
+		assert!(record.validate().is_ok());
+		assert_eq!(record.to_string(), "R-936da01f-9abd-4d9d-80c7-02af85c822a8");
+		assert!(User::try_from("U-").unwrap().validate().is_err());
+		assert!(User::try_from("U-Neos").unwrap().uuid().is_none());
+	}
+
+	#[test]
+	fn borrow() {
+		let mut users = std::collections::HashSet::new();
+		users.insert(User::try_from("U-Neos").unwrap());
+		assert!(users.contains("U-Neos"));
+	}
+
+	#[test]
+	fn normalized() {
+		let id = User::try_from("U-Foo").unwrap();
+		assert_eq!(id.normalized(), User::try_from("U-fOO").unwrap().normalized());
+		assert!(id.normalized().matches(&User::try_from("U-FOO").unwrap()));
+		let username: Normalized<String> = serde_json::from_str("\"Foo\"").unwrap();
+		assert_eq!(username.as_str(), "foo");
+	}
+
+	#[test]
+	fn any() {
+		let id: Any = serde_json::from_str("\"MSG-abc\"").unwrap();
+		assert_eq!(id, Any::Message(Message::try_from("MSG-abc").unwrap()));
+		let id: Any = serde_json::from_str("\"M-abc\"").unwrap();
+		assert!(matches!(id, Any::Machine(_)));
+		let id: Any = serde_json::from_str("\"T-abc\"").unwrap();
+		assert!(matches!(id, Any::Transaction(_)));
+	}
+
+	#[test]
+	fn prefix_constants() {
+		assert_eq!(
+			Any::PREFIXES,
+			[
+				User::PREFIX,
+				Group::PREFIX,
+				Session::PREFIX,
+				Record::PREFIX,
+				Machine::PREFIX,
+				Message::PREFIX,
+				Submission::PREFIX,
+				Transaction::PREFIX,
+			]
+		);
+		assert_eq!(Owner::PREFIXES, [User::PREFIX, Group::PREFIX, Machine::PREFIX]);
+	}
+
+	#[cfg(feature = "rand_util")]
+	#[test]
+	fn random_message() {
+		let id = Message::random();
+		assert_eq!(id.uuid().map(|uuid| uuid.get_version_num()), Some(4));
+		assert_ne!(id, Message::random());
+	}
+
+	#[test]
+	fn owner() {
+		let id: Owner = serde_json::from_str("\"M-abc\"").unwrap();
+		assert_eq!(id.as_ref(), "M-abc");
+		assert!(Machine::try_from(Any::from(id.clone())).is_ok());
+		assert_eq!(Owner::try_from(Any::from(id.clone())), Ok(id));
+
+		let record = Any::from(Record::try_from("R-abc").unwrap());
+		assert_eq!(Owner::try_from(record.clone()), Err(record));
+
+		let err = serde_json::from_str::<Owner>("\"R-abc\"").unwrap_err();
+		assert!(err.to_string().contains("one of `U-` `G-` `M-`"), "{err}");
+		assert!("X-abc".parse::<Any>().unwrap_err().contains("`SUB-` `T-`"));
+	}
 }
@@ -47,9 +47,15 @@
 // azure url const API_BASE_URI: &str = "https://api.neos.com/api";
 const API_BASE_URI: &str = "https://cloudx.azurewebsites.net/api";
 
+pub mod conversations;
+pub mod diagnostics;
 pub mod id;
 pub mod model;
+pub mod presence;
 pub mod query;
+pub mod rich_text;
+pub mod session_filter;
+pub mod session_watcher;
 
 // The models are split into slightly smaller files in order to avoid a really
 // long single file.
@@ -11,14 +11,18 @@ pub struct CreditTransaction {
 	pub from_user_id: Option<crate::id::User>,
 	/// How much of the token was involved in the transaction
 	pub amount: f64,
-	/// Who received the credits
+	/// The type of the transaction
 	pub transaction_type: crate::model::TransactionType,
 	/// A message attached to the transaction
-	#[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnNull")]
 	#[serde(default)]
 	pub comment: String,
 	/// If the transaction is anonymous or not
-	#[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnNull")]
 	#[serde(default)]
 	pub anonymous: bool,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
 }
@@ -0,0 +1,70 @@
+//! JSON of models that the tests share, so that they only need to override
+//! the fields they care about.
+
+use serde_json::{json, Value};
+
+/// An offline user's status
+pub fn user_status() -> Value {
+	json!({
+		"onlineStatus": "Offline",
+		"currentSessionAccessLevel": 0,
+		"currentSessionHidden": false,
+		"currentHosting": false,
+		"outputDevice": "Unknown",
+		"isMobile": false,
+		"activeSessions": [],
+	})
+}
+
+/// An accepted friend `U-friend` of `U-test`, with [`user_status`]
+pub fn friend() -> Value {
+	json!({
+		"id": "U-friend",
+		"friendUsername": "Friend",
+		"friendStatus": "Accepted",
+		"isAccepted": true,
+		"userStatus": user_status(),
+		"ownerId": "U-test",
+	})
+}
+
+/// A public session `S-session` without any users
+pub fn session_info() -> Value {
+	json!({
+		"name": "Session",
+		"description": null,
+		"tags": [],
+		"sessionId": "S-session",
+		"normalizedSessionId": "s-session",
+		"hostUserId": "U-host",
+		"hostMachineId": "machine",
+		"hostUsername": "host",
+		"compatibilityHash": "hash",
+		"neosVersion": "2022.1.28.1310",
+		"headlessHost": true,
+		"sessionURLs": [],
+		"sessionUsers": [],
+		"thumbnail": null,
+		"joinedUsers": 0,
+		"activeUsers": 0,
+		"totalJoinedUsers": 0,
+		"totalActiveUsers": 0,
+		"maxUsers": 16,
+		"mobileFriendly": false,
+		"sessionBeginTime": "2023-04-01T12:00:00Z",
+		"lastUpdate": "2023-04-01T12:00:00Z",
+		"accessLevel": "Anyone",
+		"hasEnded": false,
+		"isValid": true,
+	})
+}
+
+/// A user of a session, who is present in VR
+pub fn session_user(username: &str) -> Value {
+	json!({
+		"username": username,
+		"userID": format!("U-{username}"),
+		"isPresent": true,
+		"outputDevice": "VR",
+	})
+}
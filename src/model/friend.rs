@@ -6,11 +6,8 @@ use time::OffsetDateTime;
 /// Details about a friend/contact.
 ///
 /// The response from the API at `users/{user_id}/friends`.
-#[cfg_attr(
-	feature = "api_client",
-	doc = "Can be gotten with
-	[`crate::api_client::NeosAuthenticated::get_friends`]."
-)]
+///
+/// Can be gotten with [`crate::query::Friends`].
 pub struct Friend {
 	/// The U-username form of ID
 	pub id: crate::id::User,
@@ -18,8 +15,8 @@ pub struct Friend {
 	/// The actual username
 	pub username: String,
 	#[serde(rename = "friendStatus")]
-	/// The status of the friendship
-	pub friendship_status: String,
+	/// The status of the friendship, from the owner's side
+	pub friendship_status: crate::model::FriendStatus,
 	/// If the friendship has been accepted
 	pub is_accepted: bool,
 	#[serde(rename = "userStatus")]
@@ -36,4 +33,82 @@ pub struct Friend {
 	pub latest_message_time: Option<OffsetDateTime>,
 	/// The U-username form of ID of whose friend the details are for.
 	pub owner_id: crate::id::Owner,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Friend {
+	#[must_use]
+	/// If both the owner and the user have accepted the friendship
+	pub fn is_friend(&self) -> bool {
+		self.friendship_status == crate::model::FriendStatus::Accepted
+			&& self.is_accepted
+	}
+
+	#[must_use]
+	/// If the user has sent a friend request to the owner that hasn't been
+	/// answered yet
+	pub fn is_pending_incoming(&self) -> bool {
+		self.friendship_status == crate::model::FriendStatus::Requested
+	}
+
+	#[must_use]
+	/// If the owner has sent a friend request to the user that hasn't been
+	/// accepted yet
+	pub fn is_pending_outgoing(&self) -> bool {
+		self.friendship_status == crate::model::FriendStatus::Accepted
+			&& !self.is_accepted
+	}
+
+	#[must_use]
+	/// If the owner has ignored the user's friend request
+	pub fn is_ignored(&self) -> bool {
+		self.friendship_status == crate::model::FriendStatus::Ignored
+	}
+
+	#[must_use]
+	/// If the owner has blocked the user
+	pub fn is_blocked(&self) -> bool {
+		self.friendship_status == crate::model::FriendStatus::Blocked
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Friend;
+	use crate::model::FriendStatus;
+
+	/// The accepted friend fixture with the given status
+	fn friend(status: &str) -> Friend {
+		let mut json = crate::model::fixtures::friend();
+		json["friendStatus"] = status.into();
+		serde_json::from_value(json).expect("friend to deserialize")
+	}
+
+	#[test]
+	fn friendship_states() {
+		assert!(friend("Accepted").is_friend());
+		assert!(friend("Requested").is_pending_incoming());
+		assert!(friend("Ignored").is_ignored());
+		assert!(friend("Blocked").is_blocked());
+
+		let outgoing = Friend { is_accepted: false, ..friend("Accepted") };
+		assert!(outgoing.is_pending_outgoing());
+		assert!(!outgoing.is_friend());
+	}
+
+	#[test]
+	fn unknown_status() {
+		let friend = friend("Muted");
+		assert_eq!(
+			friend.friendship_status,
+			FriendStatus::Other("Muted".to_owned())
+		);
+		assert_eq!(
+			serde_json::to_value(&friend).unwrap()["friendStatus"],
+			serde_json::json!("Muted")
+		);
+	}
 }
@@ -1,18 +1,18 @@
-use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 #[derive(
 	Debug,
 	Clone,
 	PartialEq,
 	Eq,
 	Hash,
-	Serialize,
-	Deserialize,
-	strum::Display,
+	SerializeDisplay,
+	DeserializeFromStr,
 	strum::EnumString,
-	strum::AsRefStr,
 	strum::EnumVariantNames,
 )]
 /// The friendship status with a Neos user
+///
+/// Statuses that aren't known yet are kept as [`FriendStatus::Other`].
 pub enum FriendStatus {
 	/// Not friends
 	None,
@@ -26,4 +26,27 @@ pub enum FriendStatus {
 	Blocked,
 	/// Accepted the user as a friend
 	Accepted,
+	#[strum(default)]
+	/// A status that isn't known to this crate yet
+	Other(String),
+}
+
+impl AsRef<str> for FriendStatus {
+	fn as_ref(&self) -> &str {
+		match self {
+			Self::None => "None",
+			Self::SearchResult => "SearchResult",
+			Self::Requested => "Requested",
+			Self::Ignored => "Ignored",
+			Self::Blocked => "Blocked",
+			Self::Accepted => "Accepted",
+			Self::Other(value) => value,
+		}
+	}
+}
+
+impl std::fmt::Display for FriendStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.as_ref())
+	}
 }
@@ -5,11 +5,8 @@ use serde::{Deserialize, Serialize};
 /// Details about a Neos group.
 ///
 /// The response from the API at `groups/{group_id}`.
-#[cfg_attr(
-	feature = "api_client",
-	doc = "Can be gotten with
-	[`api_client::Neos::get_group`](crate::api_client::Neos::get_group)."
-)]
+///
+/// Can be gotten with [`crate::query::GroupInfo`].
 pub struct Group {
 	/// The G-groupname form of ID
 	pub id: crate::id::Group,
@@ -22,4 +19,8 @@ pub struct Group {
 	pub quota_bytes: u64,
 	/// How much storage quota the group has used.
 	pub used_bytes: u64,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
 }
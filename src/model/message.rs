@@ -6,7 +6,7 @@ use time::{serde::rfc3339, OffsetDateTime};
 /// Short description of a session's user.
 pub struct Message {
 	/// An UUID prefixed with `MSG-`
-	pub id: String,
+	pub id: crate::id::Message,
 	/// The owner, so most likely the logged in user
 	pub owner_id: crate::id::User,
 	/// The sender of the message
@@ -26,6 +26,10 @@ pub struct Message {
 	#[serde(with = "crate::util::opt_rfc3339")]
 	/// When the message was sent
 	pub read_time: Option<OffsetDateTime>,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Message {
@@ -58,45 +62,374 @@ impl Message {
 			send_time: now,
 			last_update_time: now,
 			read_time: None,
+			extra: serde_json::Map::new(),
 		}
 	}
 
 	#[cfg(feature = "rand_util")]
 	#[must_use]
-	/// Generates a new pseudorandom ID for a message
-	pub fn new_id() -> String {
-		"MSG-".to_owned() + &crate::util::random_ascii_string(24)
+	/// Creates a new text message
+	pub fn text(
+		text: impl Into<String>, owner_and_sender: crate::id::User,
+		recipient: crate::id::User,
+	) -> Self {
+		Self::new(MessageContents::Text(text.into()), owner_and_sender, recipient)
+	}
+
+	#[cfg(feature = "rand_util")]
+	#[must_use]
+	/// Creates a new invite to the session.
+	///
+	/// Only the details that the recipient's client uses to show and join the
+	/// session are sent along, so the users and nested sessions are left out.
+	pub fn invite(
+		session: &crate::model::SessionInfo, owner_and_sender: crate::id::User,
+		recipient: crate::id::User,
+	) -> Self {
+		let mut session = session.clone();
+		session.users.clear();
+		session.parent_session_ids.clear();
+		session.nested_session_ids.clear();
+		session.extra.clear();
+
+		Self::new(
+			MessageContents::SessionInvite(Box::new(session)),
+			owner_and_sender,
+			recipient,
+		)
 	}
+
+	#[cfg(feature = "rand_util")]
+	#[must_use]
+	/// Creates a new message that sends an item, such as an inventory record.
+	///
+	/// Only the details that are needed for spawning the item are sent along, so
+	/// the submissions, asset manifest and path of the record are left out.
+	pub fn object(
+		record: &crate::model::Record, owner_and_sender: crate::id::User,
+		recipient: crate::id::User,
+	) -> Self {
+		let mut record = record.clone();
+		record.submissions.clear();
+		record.neos_db_manifest.clear();
+		record.path.clear();
+		record.extra.clear();
+
+		Self::new(
+			MessageContents::Object(Box::new(record)),
+			owner_and_sender,
+			recipient,
+		)
+	}
+
+	#[cfg(feature = "rand_util")]
+	#[must_use]
+	/// Creates a new voice message of an uploaded audio clip
+	pub fn sound(
+		asset_uri: crate::AssetUrl, owner_and_sender: crate::id::User,
+		recipient: crate::id::User,
+	) -> Self {
+		let now = OffsetDateTime::now_utc();
+		let record = crate::model::Record {
+			id: crate::id::Record::random(),
+			owner_id: owner_and_sender.clone().into(),
+			asset_uri,
+			global_version: 0,
+			local_version: 0,
+			last_modifying_user_id: owner_and_sender.clone(),
+			last_modifying_machine_id: String::new(),
+			name: "Voice message".to_owned(),
+			description: String::new(),
+			record_type: "audio".to_owned(),
+			owner_name: String::new(),
+			tags: Vec::new(),
+			path: String::new(),
+			thumbnail_uri: None,
+			last_modification_time: now,
+			creation_time: Some(now),
+			first_publish_time: None,
+			is_public: false,
+			is_for_patrons: false,
+			is_listed: false,
+			visits: 0,
+			rating: 0.0,
+			random_order: 0,
+			submissions: Vec::new(),
+			neos_db_manifest: Vec::new(),
+			extra: serde_json::Map::new(),
+		};
+
+		Self::new(
+			MessageContents::Sound(Box::new(record)),
+			owner_and_sender,
+			recipient,
+		)
+	}
+
+	#[cfg(feature = "rand_util")]
+	#[must_use]
+	/// Generates a new random ID for a message
+	pub fn new_id() -> crate::id::Message { crate::id::Message::random() }
 }
 
-#[serde_with::serde_as]
 #[allow(clippy::module_name_repetitions)]
-#[derive(
-	Debug, Clone, PartialEq, Serialize, Deserialize, strum::EnumVariantNames,
-)]
-#[serde(tag = "messageType", content = "content")]
+#[derive(Debug, Clone, PartialEq, strum::EnumVariantNames)]
 /// The contents of a message combined with the `MessageType`
+///
+/// Message types that aren't known yet are kept as
+/// [`MessageContents::Other`].
 pub enum MessageContents {
 	/// A normal message
 	Text(String),
 	/// ???
-	Object(
-		#[serde_as(as = "serde_with::json::JsonString")] Box<crate::model::Record>,
-	),
+	Object(Box<crate::model::Record>),
 	/// Voice recording
-	Sound(
-		#[serde_as(as = "serde_with::json::JsonString")] Box<crate::model::Record>,
-	),
+	Sound(Box<crate::model::Record>),
 	/// Invite to a session
-	SessionInvite(
-		#[serde_as(as = "serde_with::json::JsonString")]
-		Box<crate::model::SessionInfo>,
-	),
+	SessionInvite(Box<crate::model::SessionInfo>),
 	/// NCR/KFC related most likely
-	CreditTransfer(
-		#[serde_as(as = "serde_with::json::JsonString")]
-		crate::model::CreditTransaction,
-	),
+	CreditTransfer(crate::model::CreditTransaction),
 	/// Kofi/tipping related..?
 	SugarCubes(String),
+	/// A message type that isn't known to this crate yet
+	Other {
+		/// The `messageType` of the message
+		message_type: String,
+		/// The raw content of the message
+		content: String,
+	},
+}
+
+impl MessageContents {
+	#[must_use]
+	/// The `messageType` of the message, such as `Text`
+	pub fn message_type(&self) -> &str {
+		match self {
+			Self::Text(_) => "Text",
+			Self::Object(_) => "Object",
+			Self::Sound(_) => "Sound",
+			Self::SessionInvite(_) => "SessionInvite",
+			Self::CreditTransfer(_) => "CreditTransfer",
+			Self::SugarCubes(_) => "SugarCubes",
+			Self::Other { message_type, .. } => message_type,
+		}
+	}
+}
+
+/// The API representation of the message contents, where the content is a
+/// string that's JSON for most of the message types.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RawMessageContents<C> {
+	message_type: String,
+	content: C,
+}
+
+impl Serialize for MessageContents {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		use serde::ser::Error;
+
+		let content = match self {
+			Self::Text(content)
+			| Self::SugarCubes(content)
+			| Self::Other { content, .. } => content.clone(),
+			Self::Object(record) | Self::Sound(record) => {
+				serde_json::to_string(record).map_err(S::Error::custom)?
+			}
+			Self::SessionInvite(session) => {
+				serde_json::to_string(session).map_err(S::Error::custom)?
+			}
+			Self::CreditTransfer(transaction) => {
+				serde_json::to_string(transaction).map_err(S::Error::custom)?
+			}
+		};
+
+		RawMessageContents { message_type: self.message_type().to_owned(), content }
+			.serialize(serializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for MessageContents {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		use serde::de::Error;
+
+		let RawMessageContents { message_type, content } =
+			RawMessageContents::<String>::deserialize(deserializer)?;
+
+		Ok(match message_type.as_str() {
+			"Text" => Self::Text(content),
+			"SugarCubes" => Self::SugarCubes(content),
+			"Object" => {
+				Self::Object(serde_json::from_str(&content).map_err(D::Error::custom)?)
+			}
+			"Sound" => {
+				Self::Sound(serde_json::from_str(&content).map_err(D::Error::custom)?)
+			}
+			"SessionInvite" => Self::SessionInvite(
+				serde_json::from_str(&content).map_err(D::Error::custom)?,
+			),
+			"CreditTransfer" => Self::CreditTransfer(
+				serde_json::from_str(&content).map_err(D::Error::custom)?,
+			),
+			_ => Self::Other { message_type, content },
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::MessageContents;
+
+	#[test]
+	fn other_message_type() {
+		let json = serde_json::json!({
+			"messageType": "Sticker",
+			"content": "{\"id\":1}",
+		});
+		let contents: MessageContents =
+			serde_json::from_value(json.clone()).expect("deserializing to work");
+		assert_eq!(
+			contents,
+			MessageContents::Other {
+				message_type: "Sticker".to_owned(),
+				content: "{\"id\":1}".to_owned(),
+			}
+		);
+		assert_eq!(serde_json::to_value(&contents).unwrap(), json);
+	}
+
+	#[test]
+	fn text() {
+		let json = serde_json::json!({ "messageType": "Text", "content": "Hi" });
+		let contents: MessageContents =
+			serde_json::from_value(json.clone()).expect("deserializing to work");
+		assert_eq!(contents, MessageContents::Text("Hi".to_owned()));
+		assert_eq!(serde_json::to_value(&contents).unwrap(), json);
+	}
+
+	#[cfg(feature = "rand_util")]
+	#[test]
+	fn constructors() {
+		let record: crate::model::Record =
+			serde_json::from_value(serde_json::json!({
+				"id": "R-item",
+				"ownerId": "U-sender",
+				"assetUri": "neosdb:///item.7zbson",
+				"globalVersion": 1,
+				"localVersion": 1,
+				"lastModifyingUserId": "U-sender",
+				"lastModifyingMachineId": "machine",
+				"name": "Item",
+				"recordType": "object",
+				"thumbnailUri": null,
+				"lastModificationTime": "2023-04-01T12:00:00Z",
+				"isPublic": false,
+				"isForPatrons": false,
+				"isListed": false,
+				"visits": 0,
+				"rating": 0.0,
+				"randomOrder": 0,
+			}))
+			.expect("record to deserialize");
+		let sender = crate::id::User::try_from("U-sender").unwrap();
+		let recipient = crate::id::User::try_from("U-recipient").unwrap();
+
+		let message = super::Message::object(&record, sender.clone(), recipient);
+		assert_eq!(message.content.message_type(), "Object");
+		assert_eq!(message.non_owner_id().as_ref(), "U-recipient");
+		let json = serde_json::to_value(&message).unwrap();
+		let content: serde_json::Value =
+			serde_json::from_str(json["content"].as_str().unwrap()).unwrap();
+		assert_eq!(content["id"], "R-item");
+		assert_eq!(content["assetUri"], "neosdb:///item.7zbson");
+		assert_eq!(content["submissions"], serde_json::json!([]));
+		assert_eq!(
+			serde_json::from_value::<super::Message>(json).unwrap(),
+			message
+		);
+
+		let message = super::Message::text("Hi", sender.clone(), sender);
+		assert_eq!(message.content, MessageContents::Text("Hi".to_owned()));
+		assert!(message.read_time.is_none());
+	}
+
+	#[cfg(feature = "rand_util")]
+	#[test]
+	fn invite() {
+		let mut session = crate::model::fixtures::session_info();
+		session["sessionUsers"] =
+			serde_json::json!([crate::model::fixtures::session_user("a")]);
+		session["parentSessionIds"] = serde_json::json!(["S-parent"]);
+		let session: crate::model::SessionInfo =
+			serde_json::from_value(session).expect("session to deserialize");
+		let sender = crate::id::User::try_from("U-sender").unwrap();
+		let recipient = crate::id::User::try_from("U-recipient").unwrap();
+
+		let message = super::Message::invite(&session, sender, recipient);
+		let json = serde_json::to_value(&message).unwrap();
+		assert_eq!(json["messageType"], "SessionInvite");
+		let content: serde_json::Value =
+			serde_json::from_str(json["content"].as_str().unwrap()).unwrap();
+		assert_eq!(content["sessionId"], "S-session");
+		assert_eq!(content["sessionUsers"], serde_json::json!([]));
+		assert_eq!(content["parentSessionIds"], serde_json::json!([]));
+	}
+
+	#[cfg(feature = "rand_util")]
+	#[test]
+	fn sound() {
+		let asset =
+			crate::AssetUrl::try_from("neosdb:///voice.ogg").expect("valid asset");
+		let sender = crate::id::User::try_from("U-sender").unwrap();
+		let recipient = crate::id::User::try_from("U-recipient").unwrap();
+
+		let message = super::Message::sound(asset, sender, recipient);
+		let json = serde_json::to_value(&message).unwrap();
+		assert_eq!(json["messageType"], "Sound");
+		let content: serde_json::Value =
+			serde_json::from_str(json["content"].as_str().unwrap()).unwrap();
+		assert!(content["id"].as_str().unwrap().starts_with("R-"));
+		assert_eq!(content["ownerId"], "U-sender");
+		assert_eq!(content["assetUri"], "neosdb:///voice.ogg");
+		assert_eq!(content["recordType"], "audio");
+		assert_eq!(
+			serde_json::from_value::<super::Message>(json).unwrap(),
+			message
+		);
+	}
+
+	#[test]
+	fn extra_fields_round_trip() {
+		let json = serde_json::json!({
+			"id": "MSG-message",
+			"ownerId": "U-owner",
+			"senderId": "U-sender",
+			"recipientId": "U-owner",
+			"messageType": "Text",
+			"content": "Hi",
+			"sendTime": "2023-04-01T12:00:00Z",
+			"lastUpdateTime": "2023-04-01T12:00:00Z",
+			"readTime": null,
+			"somethingNew": { "nested": true },
+		});
+		let message: super::Message =
+			serde_json::from_value(json.clone()).expect("deserializing to work");
+		assert_eq!(message.content, MessageContents::Text("Hi".to_owned()));
+
+		let serialized = serde_json::to_value(&message).unwrap();
+		if cfg!(feature = "extra_fields") {
+			assert_eq!(message.extra.len(), 1, "{:?}", message.extra);
+			assert_eq!(serialized, json);
+		} else {
+			assert!(message.extra.is_empty());
+			assert!(serialized.get("somethingNew").is_none());
+			assert_eq!(serialized["messageType"], "Text");
+		}
+	}
 }
@@ -1,8 +1,17 @@
 //! Models of the responses of Neos' API.
+//!
+//! The models have an `extra` field for the fields which this crate doesn't
+//! know about. With the optional `extra_fields` feature, the unknown fields are
+//! kept in it and serialized back, so that round-tripping a model doesn't lose
+//! data. Without the feature the field is always empty and ignored, but it
+//! still exists so that enabling the feature anywhere in the dependency graph
+//! doesn't break struct literals.
 
 #![allow(clippy::struct_excessive_bools)]
 
 mod credit_transaction;
+#[cfg(test)]
+pub(crate) mod fixtures;
 mod friend;
 mod friend_status;
 mod group;
@@ -15,6 +24,7 @@ mod record;
 mod record_id;
 mod rsa_parameters_data;
 mod session_access_level;
+mod session_url;
 mod session_user;
 mod sessions_info;
 mod submission;
@@ -38,6 +48,7 @@ pub use record::*;
 pub use record_id::*;
 pub use rsa_parameters_data::*;
 pub use session_access_level::*;
+pub use session_url::*;
 pub use session_user::*;
 pub use sessions_info::*;
 pub use submission::*;
@@ -8,4 +8,8 @@ pub struct NeosDBAsset {
 	pub hash: String,
 	/// How large the asset is
 	pub bytes: u64,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
 }
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 
 #[derive(
 	Debug,
@@ -6,14 +6,14 @@ use serde::{Deserialize, Serialize};
 	PartialEq,
 	Eq,
 	Hash,
-	Deserialize,
-	Serialize,
-	strum::Display,
+	SerializeDisplay,
+	DeserializeFromStr,
 	strum::EnumString,
-	strum::AsRefStr,
 	strum::EnumVariantNames,
 )]
 /// The online status of a Neos user.
+///
+/// Statuses that aren't known yet are kept as [`OnlineStatus::Other`].
 pub enum OnlineStatus {
 	/// The user is online
 	Online,
@@ -25,6 +25,28 @@ pub enum OnlineStatus {
 	Busy,
 	/// The user is offline
 	Offline,
+	#[strum(default)]
+	/// A status that isn't known to this crate yet
+	Other(String),
+}
+
+impl AsRef<str> for OnlineStatus {
+	fn as_ref(&self) -> &str {
+		match self {
+			Self::Online => "Online",
+			Self::Invisible => "Invisible",
+			Self::Away => "Away",
+			Self::Busy => "Busy",
+			Self::Offline => "Offline",
+			Self::Other(value) => value,
+		}
+	}
+}
+
+impl std::fmt::Display for OnlineStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.as_ref())
+	}
 }
 
 impl OnlineStatus {
@@ -35,7 +57,28 @@ impl OnlineStatus {
 			Self::Online => (0, 255, 0),
 			Self::Away => (255, 200, 0),
 			Self::Busy => (255, 0, 0),
-			Self::Offline | Self::Invisible => (127, 127, 127),
+			Self::Offline | Self::Invisible | Self::Other(_) => (127, 127, 127),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::OnlineStatus;
+
+	#[test]
+	fn known() {
+		let val: OnlineStatus =
+			serde_json::from_str("\"Away\"").expect("deserializing to work");
+		assert_eq!(val, OnlineStatus::Away);
+		assert_eq!(serde_json::to_string(&val).unwrap(), "\"Away\"");
+	}
+
+	#[test]
+	fn other() {
+		let val: OnlineStatus =
+			serde_json::from_str("\"Sleeping\"").expect("deserializing to work");
+		assert_eq!(val, OnlineStatus::Other("Sleeping".to_owned()));
+		assert_eq!(serde_json::to_string(&val).unwrap(), "\"Sleeping\"");
+	}
+}
@@ -6,18 +6,16 @@ use serde::{Deserialize, Serialize};
 	PartialEq,
 	Eq,
 	Hash,
-	Serialize,
 	strum::FromRepr,
-	strum::Display,
 	strum::EnumString,
-	strum::AsRefStr,
 	strum::EnumVariantNames,
 )]
 #[repr(u8)]
 /// The type of output device that the user is using.
 ///
 /// The API is inconsistent, sometimes representing this as a string and
-/// sometimes as a number.
+/// sometimes as a number. Numbers that aren't known yet are kept as
+/// [`OutputDevice::Other`], and strings as [`OutputDevice::OtherStr`].
 pub enum OutputDevice {
 	/// Output device not known
 	Unknown = 0,
@@ -26,11 +24,69 @@ pub enum OutputDevice {
 	/// Desktop
 	Screen = 2,
 	#[strum(to_string = "VR")]
-	#[serde(rename = "VR")]
 	/// Virtual Reality
 	Vr = 3,
 	/// In game camera
 	Camera = 4,
+	#[strum(disabled)]
+	/// A device that isn't known to this crate yet, which is (de)serialized as
+	/// a number
+	Other(u8),
+	#[strum(disabled)]
+	/// A device that isn't known to this crate yet, which is (de)serialized as
+	/// a string
+	OtherStr(String),
+}
+
+impl OutputDevice {
+	#[must_use]
+	/// The numeric representation of the output device, if it's known
+	pub const fn repr(&self) -> Option<u8> {
+		Some(match self {
+			Self::Unknown => 0,
+			Self::Headless => 1,
+			Self::Screen => 2,
+			Self::Vr => 3,
+			Self::Camera => 4,
+			Self::Other(repr) => *repr,
+			Self::OtherStr(_) => return None,
+		})
+	}
+
+	/// The string representation of the output device, or the number if it
+	/// only has one
+	const fn name_or_repr(&self) -> Result<&str, u8> {
+		Ok(match self {
+			Self::Unknown => "Unknown",
+			Self::Headless => "Headless",
+			Self::Screen => "Screen",
+			Self::Vr => "VR",
+			Self::Camera => "Camera",
+			Self::OtherStr(name) => name.as_str(),
+			Self::Other(repr) => return Err(*repr),
+		})
+	}
+}
+
+impl std::fmt::Display for OutputDevice {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.name_or_repr() {
+			Ok(name) => f.write_str(name),
+			Err(repr) => write!(f, "{repr}"),
+		}
+	}
+}
+
+impl Serialize for OutputDevice {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::ser::Serializer,
+	{
+		match self.name_or_repr() {
+			Ok(name) => serializer.serialize_str(name),
+			Err(repr) => serializer.serialize_u8(repr),
+		}
+	}
 }
 
 // Allow the OutputDevice to be either represented as a string or number in
@@ -42,7 +98,7 @@ impl<'de> Deserialize<'de> for OutputDevice {
 	{
 		struct OutputDeviceVisitor;
 
-		impl<'de> serde::de::Visitor<'de> for OutputDeviceVisitor {
+		impl serde::de::Visitor<'_> for OutputDeviceVisitor {
 			type Value = OutputDevice;
 
 			fn expecting(
@@ -55,12 +111,7 @@ impl<'de> Deserialize<'de> for OutputDevice {
 			where
 				E: serde::de::Error,
 			{
-				OutputDevice::from_repr(v).ok_or_else(|| {
-					serde::de::Error::invalid_value(
-						serde::de::Unexpected::Unsigned(v.into()),
-						&"enum u8 repr",
-					)
-				})
+				Ok(OutputDevice::from_repr(v).unwrap_or(OutputDevice::Other(v)))
 			}
 
 			fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
@@ -93,12 +144,10 @@ impl<'de> Deserialize<'de> for OutputDevice {
 			{
 				use std::str::FromStr;
 
-				OutputDevice::from_str(v).map_err(|_| {
-					serde::de::Error::invalid_value(
-						serde::de::Unexpected::Str(v),
-						&"enum str repr",
-					)
-				})
+				Ok(
+					OutputDevice::from_str(v)
+						.unwrap_or_else(|_| OutputDevice::OtherStr(v.to_owned())),
+				)
 			}
 		}
 
@@ -146,6 +195,15 @@ mod tests {
 		assert_eq!(&val, &OutputDevice::Camera);
 	}
 
+	#[test]
+	fn other() {
+		let val: OutputDevice =
+			serde_json::from_str("42").expect("deserializing from num to work");
+		assert_eq!(&val, &OutputDevice::Other(42));
+		let val = serde_json::to_string(&val).expect("serializing to work");
+		assert_eq!(&val, "42");
+	}
+
 	#[test]
 	fn unknown() {
 		let val = serde_json::to_string(&OutputDevice::Unknown)
@@ -158,4 +216,15 @@ mod tests {
 			serde_json::from_str("0").expect("deserializing from num to work");
 		assert_eq!(&val, &OutputDevice::Unknown);
 	}
+
+	#[test]
+	fn other_str() {
+		let val: OutputDevice = serde_json::from_str("\"Quest\"")
+			.expect("deserializing from str to work");
+		assert_eq!(&val, &OutputDevice::OtherStr("Quest".to_owned()));
+		assert_eq!(val.to_string(), "Quest");
+		assert_eq!(val.repr(), None);
+		let val = serde_json::to_string(&val).expect("serializing to work");
+		assert_eq!(&val, "\"Quest\"");
+	}
 }
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 
 #[derive(
 	Debug,
@@ -6,14 +6,14 @@ use serde::{Deserialize, Serialize};
 	PartialEq,
 	Eq,
 	Hash,
-	Deserialize,
-	Serialize,
-	strum::Display,
+	SerializeDisplay,
+	DeserializeFromStr,
 	strum::EnumString,
-	strum::AsRefStr,
 	strum::EnumVariantNames,
 )]
 /// The type of a ban.
+///
+/// Types that aren't known yet are kept as [`PublicBanType::Other`].
 pub enum PublicBanType {
 	/// A standard ban
 	Standard,
@@ -21,4 +21,24 @@ pub enum PublicBanType {
 	Soft,
 	/// A hard ban
 	Hard,
+	#[strum(default)]
+	/// A type that isn't known to this crate yet
+	Other(String),
+}
+
+impl AsRef<str> for PublicBanType {
+	fn as_ref(&self) -> &str {
+		match self {
+			Self::Standard => "Standard",
+			Self::Soft => "Soft",
+			Self::Hard => "Hard",
+			Self::Other(value) => value,
+		}
+	}
+}
+
+impl std::fmt::Display for PublicBanType {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.as_ref())
+	}
 }
@@ -25,7 +25,7 @@ pub struct Record {
 	/// The user readable name of the record
 	pub name: String,
 	#[serde(default)]
-	#[serde_as(deserialize_as = "serde_with::DefaultOnError")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnError")]
 	/// The user readable description of the record
 	///
 	/// Defaults to an empty string if null/none in the API.
@@ -33,16 +33,16 @@ pub struct Record {
 	/// The type of the record
 	pub record_type: String,
 	#[serde(default)]
-	#[serde_as(deserialize_as = "serde_with::DefaultOnError")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnError")]
 	/// The user readable name of the owner
 	///
 	/// Defaults to an empty string if null/none in the API.
 	pub owner_name: String,
 	#[serde(default)]
-	#[serde_as(deserialize_as = "serde_with::DefaultOnError")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnError")]
 	/// The tags of the record
 	pub tags: Vec<String>,
-	#[serde_as(deserialize_as = "serde_with::DefaultOnError")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnError")]
 	#[serde(default)]
 	/// The path to this record
 	///
@@ -74,12 +74,16 @@ pub struct Record {
 	/// Number for random ordering
 	pub random_order: u32,
 	#[serde(default)]
-	#[serde_as(deserialize_as = "serde_with::DefaultOnError")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnError")]
 	/// The record's submissions to groups
 	pub submissions: Vec<crate::model::Submission>,
 	#[serde(default)]
-	#[serde_as(deserialize_as = "serde_with::DefaultOnError")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnError")]
 	#[serde(rename = "neosDBmanifest")]
 	/// Details about the asset
 	pub neos_db_manifest: Vec<crate::model::NeosDBAsset>,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
 }
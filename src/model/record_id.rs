@@ -9,4 +9,26 @@ pub struct RecordId {
 	pub id: crate::id::Record,
 	/// The ID of the owner (`U-{uuid}` or `G-{uuid}` for example)
 	pub owner_id: crate::id::Owner,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(all(test, feature = "extra_fields"))]
+mod tests {
+	use super::RecordId;
+
+	#[test]
+	fn extra_fields_round_trip() {
+		let json = serde_json::json!({
+			"recordId": "R-record",
+			"ownerId": "U-owner",
+			"somethingNew": [1, 2, 3],
+		});
+		let record_id: RecordId =
+			serde_json::from_value(json.clone()).expect("deserializing to work");
+		assert_eq!(record_id.extra["somethingNew"], serde_json::json!([1, 2, 3]));
+		assert_eq!(serde_json::to_value(&record_id).unwrap(), json);
+	}
 }
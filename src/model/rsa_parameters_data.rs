@@ -8,4 +8,8 @@ pub struct RSAParametersData {
 	pub exponent: String,
 	/// The modulus component of the RSA public key
 	pub modulus: String,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
 }
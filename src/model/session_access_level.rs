@@ -8,20 +8,24 @@ use serde::de::{self, Deserialize, Deserializer, Visitor};
 	PartialEq,
 	Eq,
 	Hash,
-	PartialOrd,
-	Ord,
-	serde::Serialize,
-	strum::Display,
 	strum::FromRepr,
 	strum::EnumString,
-	strum::AsRefStr,
 	strum::EnumVariantNames,
 )]
 #[repr(u8)]
 /// A Neos session's access level.
 ///
 /// The API is inconsistent, sometimes representing this as a string and
-/// sometimes as a number.
+/// sometimes as a number. Numbers that aren't known yet are kept as
+/// [`SessionAccessLevel::Other`], and strings as
+/// [`SessionAccessLevel::OtherStr`].
+///
+/// Access levels are ordered by their numeric representation, so from the
+/// most private to the most open. An [`SessionAccessLevel::Other`] with the
+/// same number as a known level is ordered right after it, so that the
+/// ordering agrees with equality. Unknown numbers above the known ones are
+/// ordered as more open than [`SessionAccessLevel::Anyone`], and unknown
+/// strings after all of them, as they can't be compared otherwise.
 pub enum SessionAccessLevel {
 	/// The session is private
 	Private = 0,
@@ -35,6 +39,87 @@ pub enum SessionAccessLevel {
 	RegisteredUsers = 4,
 	/// The session is accessible to anyone
 	Anyone = 5,
+	#[strum(disabled)]
+	/// An access level that isn't known to this crate yet, which is
+	/// (de)serialized as a number
+	Other(u8),
+	#[strum(disabled)]
+	/// An access level that isn't known to this crate yet, which is
+	/// (de)serialized as a string
+	OtherStr(String),
+}
+
+impl SessionAccessLevel {
+	#[must_use]
+	/// The numeric representation of the access level, if it's known
+	pub const fn repr(&self) -> Option<u8> {
+		Some(match self {
+			Self::Private => 0,
+			Self::Lan => 1,
+			Self::Friends => 2,
+			Self::FriendsOfFriends => 3,
+			Self::RegisteredUsers => 4,
+			Self::Anyone => 5,
+			Self::Other(repr) => *repr,
+			Self::OtherStr(_) => return None,
+		})
+	}
+
+	/// The string representation of the access level, or the number if it only
+	/// has one
+	const fn name_or_repr(&self) -> Result<&str, u8> {
+		Ok(match self {
+			Self::Private => "Private",
+			Self::Lan => "Lan",
+			Self::Friends => "Friends",
+			Self::FriendsOfFriends => "FriendsOfFriends",
+			Self::RegisteredUsers => "RegisteredUsers",
+			Self::Anyone => "Anyone",
+			Self::OtherStr(name) => name.as_str(),
+			Self::Other(repr) => return Err(*repr),
+		})
+	}
+}
+
+impl PartialOrd for SessionAccessLevel {
+	fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for SessionAccessLevel {
+	fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+		fn key(level: &SessionAccessLevel) -> (bool, u8, bool, Option<&str>) {
+			match level {
+				SessionAccessLevel::OtherStr(name) => (true, 0, false, Some(name)),
+				SessionAccessLevel::Other(repr) => (false, *repr, true, None),
+				known => (false, known.repr().unwrap_or_default(), false, None),
+			}
+		}
+
+		key(self).cmp(&key(other))
+	}
+}
+
+impl fmt::Display for SessionAccessLevel {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.name_or_repr() {
+			Ok(name) => f.write_str(name),
+			Err(repr) => write!(f, "{repr}"),
+		}
+	}
+}
+
+impl serde::Serialize for SessionAccessLevel {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		match self.name_or_repr() {
+			Ok(name) => serializer.serialize_str(name),
+			Err(repr) => serializer.serialize_u8(repr),
+		}
+	}
 }
 
 // Allow the SessionAccessLevel to be either represented as a string or number
@@ -46,7 +131,7 @@ impl<'de> Deserialize<'de> for SessionAccessLevel {
 	{
 		struct SessionAccessLevelVisitor;
 
-		impl<'de> Visitor<'de> for SessionAccessLevelVisitor {
+		impl Visitor<'_> for SessionAccessLevelVisitor {
 			type Value = SessionAccessLevel;
 
 			fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
@@ -58,12 +143,10 @@ impl<'de> Deserialize<'de> for SessionAccessLevel {
 			where
 				E: de::Error,
 			{
-				SessionAccessLevel::from_repr(v).ok_or_else(|| {
-					de::Error::invalid_value(
-						serde::de::Unexpected::Unsigned(v.into()),
-						&"enum u8 repr",
-					)
-				})
+				Ok(
+					SessionAccessLevel::from_repr(v)
+						.unwrap_or(SessionAccessLevel::Other(v)),
+				)
 			}
 
 			fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
@@ -94,15 +177,56 @@ impl<'de> Deserialize<'de> for SessionAccessLevel {
 			where
 				E: de::Error,
 			{
-				SessionAccessLevel::try_from(v).map_err(|_| {
-					de::Error::invalid_value(
-						serde::de::Unexpected::Str(v),
-						&"enum str repr",
-					)
-				})
+				Ok(
+					SessionAccessLevel::try_from(v)
+						.unwrap_or_else(|_| SessionAccessLevel::OtherStr(v.to_owned())),
+				)
 			}
 		}
 
 		deserializer.deserialize_any(SessionAccessLevelVisitor)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::SessionAccessLevel;
+
+	#[test]
+	fn other() {
+		let val: SessionAccessLevel =
+			serde_json::from_str("9").expect("deserializing from num to work");
+		assert_eq!(val, SessionAccessLevel::Other(9));
+		assert_eq!(serde_json::to_string(&val).unwrap(), "9");
+		assert!(val > SessionAccessLevel::Anyone);
+
+		let val: SessionAccessLevel = serde_json::from_str("\"Contacts\"")
+			.expect("deserializing from str to work");
+		assert_eq!(val, SessionAccessLevel::OtherStr("Contacts".to_owned()));
+		assert_eq!(val.to_string(), "Contacts");
+		assert_eq!(serde_json::to_string(&val).unwrap(), "\"Contacts\"");
+		assert!(val > SessionAccessLevel::Other(9));
+	}
+
+	#[test]
+	fn ordering_agrees_with_equality() {
+		use std::cmp::Ordering;
+
+		let known = SessionAccessLevel::Friends;
+		let other = SessionAccessLevel::Other(2);
+		assert_ne!(known, other);
+		assert_eq!(known.cmp(&other), Ordering::Less);
+		assert_eq!(other.cmp(&known), Ordering::Greater);
+		assert!(other < SessionAccessLevel::FriendsOfFriends);
+
+		let mut levels = vec![
+			other.clone(),
+			known.clone(),
+			SessionAccessLevel::Other(2),
+			SessionAccessLevel::Friends,
+		];
+		levels.sort();
+		levels.dedup();
+		assert_eq!(levels, [known, other]);
+	}
+}
@@ -0,0 +1,285 @@
+/// The host, port and path parts of a session's URL.
+///
+/// Such as `{host}:{port}/{path}` of `lnl-nat://{host}:{port}/{path}`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionAddress {
+	/// The host, an IP address or an identifier of the transport
+	pub host: String,
+	/// The port, if any
+	pub port: Option<u16>,
+	/// The part after the host, without the leading `/`
+	pub path: Option<String>,
+}
+
+impl SessionAddress {
+	fn parse(authority_and_path: &str) -> Result<Self, &'static str> {
+		let (authority, path) = match authority_and_path.split_once('/') {
+			Some((authority, path)) => (authority, Some(path.to_owned())),
+			None => (authority_and_path, None),
+		};
+
+		// IPv6 addresses are wrapped in brackets, like `[::1]:1234`
+		let port_separator = authority
+			.rfind(':')
+			.filter(|index| authority.rfind(']').is_none_or(|end| end < *index));
+		let (host, port) = match port_separator {
+			Some(index) => (
+				&authority[..index],
+				Some(authority[index + 1..].parse().map_err(|_| "invalid port")?),
+			),
+			None => (authority, None),
+		};
+		if host.is_empty() {
+			return Err("missing host");
+		}
+
+		Ok(Self { host: host.to_owned(), port, path })
+	}
+
+	#[must_use]
+	/// The session that the path points to, if it's a session ID
+	pub fn session_id(&self) -> Option<crate::id::Session> {
+		crate::id::Session::try_from(self.path.clone()?).ok()
+	}
+}
+
+impl std::fmt::Display for SessionAddress {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&self.host)?;
+		if let Some(port) = self.port {
+			write!(f, ":{port}")?;
+		}
+		if let Some(path) = &self.path {
+			write!(f, "/{path}")?;
+		}
+		Ok(())
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A link to a Neos session, in one of the custom protocols.
+///
+/// # Example usage
+///
+/// ```
+/// use neos::model::SessionUrl;
+///
+/// let url = SessionUrl::try_from("lnl-nat://abc123/S-some-session").unwrap();
+/// assert!(url.is_lnl_nat());
+/// assert_eq!(
+/// 	url.session_id().unwrap(),
+/// 	neos::id::Session::try_from("S-some-session").unwrap()
+/// );
+///
+/// let join_url: SessionUrl =
+/// 	neos::id::Session::try_from("S-some-session").unwrap().into();
+/// assert_eq!(join_url.to_string(), "neos-session:///S-some-session");
+/// ```
+pub enum SessionUrl {
+	/// A direct `LiteNetLib` connection, `lnl://{host}:{port}/{path}`
+	Lnl(SessionAddress),
+	/// A NAT punched `LiteNetLib` connection, `lnl-nat://{host}/{path}`
+	LnlNat(SessionAddress),
+	/// A Steam networking connection, `neos-steam://{host}/{path}`
+	Steam(SessionAddress),
+	/// A transport independent link to join a session with,
+	/// `neos-session:///{session_id}`
+	Session(crate::id::Session),
+	/// A link with an unknown protocol, or one that couldn't be parsed when
+	/// deserializing
+	Other(String),
+}
+
+impl SessionUrl {
+	const LNL: &'static str = "lnl://";
+	const LNL_NAT: &'static str = "lnl-nat://";
+	const SESSION: &'static str = "neos-session:///";
+	const STEAM: &'static str = "neos-steam://";
+
+	#[must_use]
+	/// The session that the URL points to, if it's known
+	pub fn session_id(&self) -> Option<crate::id::Session> {
+		match self {
+			Self::Lnl(address) | Self::LnlNat(address) | Self::Steam(address) => {
+				address.session_id()
+			}
+			Self::Session(session_id) => Some(session_id.clone()),
+			Self::Other(_) => None,
+		}
+	}
+
+	#[must_use]
+	/// The host, port and path of the URL, if it has them
+	pub const fn address(&self) -> Option<&SessionAddress> {
+		match self {
+			Self::Lnl(address) | Self::LnlNat(address) | Self::Steam(address) => {
+				Some(address)
+			}
+			Self::Session(_) | Self::Other(_) => None,
+		}
+	}
+
+	#[must_use]
+	/// If is a direct `lnl://` URL
+	pub const fn is_lnl(&self) -> bool { matches!(self, Self::Lnl(_)) }
+
+	#[must_use]
+	/// If is a NAT punched `lnl-nat://` URL
+	pub const fn is_lnl_nat(&self) -> bool { matches!(self, Self::LnlNat(_)) }
+
+	#[must_use]
+	/// If is a `neos-steam://` URL
+	pub const fn is_steam(&self) -> bool { matches!(self, Self::Steam(_)) }
+
+	#[must_use]
+	/// If is a `neos-session:///` URL
+	pub const fn is_session(&self) -> bool { matches!(self, Self::Session(_)) }
+}
+
+impl TryFrom<&str> for SessionUrl {
+	type Error = &'static str;
+
+	fn try_from(url: &str) -> Result<Self, Self::Error> {
+		if let Some(session_id) = url.strip_prefix(Self::SESSION) {
+			return crate::id::Session::try_from(session_id.to_owned())
+				.map(Self::Session);
+		}
+		if let Some(rest) = url.strip_prefix(Self::LNL) {
+			return SessionAddress::parse(rest).map(Self::Lnl);
+		}
+		if let Some(rest) = url.strip_prefix(Self::LNL_NAT) {
+			return SessionAddress::parse(rest).map(Self::LnlNat);
+		}
+		if let Some(rest) = url.strip_prefix(Self::STEAM) {
+			return SessionAddress::parse(rest).map(Self::Steam);
+		}
+		if url.contains("://") {
+			return Ok(Self::Other(url.to_owned()));
+		}
+
+		Err("should be an URL with a protocol such as `lnl-nat://`")
+	}
+}
+
+impl std::str::FromStr for SessionUrl {
+	type Err = &'static str;
+
+	fn from_str(url: &str) -> Result<Self, Self::Err> { Self::try_from(url) }
+}
+
+impl From<crate::id::Session> for SessionUrl {
+	/// Creates a `neos-session:///` link to join the session with
+	fn from(session_id: crate::id::Session) -> Self { Self::Session(session_id) }
+}
+
+impl std::fmt::Display for SessionUrl {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Lnl(address) => write!(f, "{}{address}", Self::LNL),
+			Self::LnlNat(address) => write!(f, "{}{address}", Self::LNL_NAT),
+			Self::Steam(address) => write!(f, "{}{address}", Self::STEAM),
+			Self::Session(session_id) => {
+				write!(f, "{}{}", Self::SESSION, session_id.as_ref())
+			}
+			Self::Other(url) => f.write_str(url),
+		}
+	}
+}
+
+impl<'de> serde::de::Deserialize<'de> for SessionUrl {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::de::Deserializer<'de>,
+	{
+		struct SessionUrlVisitor;
+
+		impl serde::de::Visitor<'_> for SessionUrlVisitor {
+			type Value = SessionUrl;
+
+			fn expecting(
+				&self, formatter: &mut std::fmt::Formatter,
+			) -> std::fmt::Result {
+				formatter.write_str("a session URL string")
+			}
+
+			// Links are kept as they were when they can't be parsed, so that they
+			// aren't lost and serialize back unchanged.
+			fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+			where
+				E: serde::de::Error,
+			{
+				Ok(
+					SessionUrl::try_from(v)
+						.unwrap_or_else(|_| SessionUrl::Other(v.to_owned())),
+				)
+			}
+		}
+
+		deserializer.deserialize_str(SessionUrlVisitor)
+	}
+}
+
+impl serde::ser::Serialize for SessionUrl {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::ser::Serializer,
+	{
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{SessionAddress, SessionUrl};
+
+	#[test]
+	fn round_trips() {
+		for url in [
+			"lnl://127.0.0.1:12345/S-abc",
+			"lnl://[::1]:12345/",
+			"lnl-nat://abc123/S-U-user:world",
+			"neos-steam://76561198000000000/S-abc",
+			"neos-session:///S-abc",
+			"https://example.com/",
+		] {
+			let parsed = SessionUrl::try_from(url).expect("URL to parse");
+			assert_eq!(parsed.to_string(), url);
+		}
+	}
+
+	#[test]
+	fn lnl() {
+		let url = SessionUrl::try_from("lnl://[::1]:12345/S-abc").unwrap();
+		assert_eq!(
+			url,
+			SessionUrl::Lnl(SessionAddress {
+				host: "[::1]".to_owned(),
+				port: Some(12345),
+				path: Some("S-abc".to_owned()),
+			})
+		);
+		assert!(url.session_id().is_some());
+	}
+
+	#[test]
+	fn invalid() {
+		assert!(SessionUrl::try_from("neos-session:///U-user").is_err());
+		assert!(SessionUrl::try_from("lnl://host:port/").is_err());
+		assert!(SessionUrl::try_from("lnl-nat:///S-abc").is_err());
+		assert!(SessionUrl::try_from("S-abc").is_err());
+	}
+
+	#[test]
+	fn deserializing_is_lossless() {
+		let json = serde_json::json!([
+			"lnl-nat:///S-a",
+			"lnl://host:/S-a",
+			"neos-steam://1/S-a"
+		]);
+		let urls: Vec<SessionUrl> = serde_json::from_value(json.clone()).unwrap();
+		assert_eq!(urls[0], SessionUrl::Other("lnl-nat:///S-a".to_owned()));
+		assert_eq!(urls[1], SessionUrl::Other("lnl://host:/S-a".to_owned()));
+		assert!(urls[2].is_steam());
+		assert_eq!(serde_json::to_value(&urls).unwrap(), json);
+	}
+}
@@ -13,4 +13,8 @@ pub struct SessionUser {
 	pub is_present: bool,
 	/// The output device type of the user
 	pub output_device: crate::model::OutputDevice,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
 }
@@ -9,7 +9,7 @@ use time::{serde::rfc3339, OffsetDateTime};
 pub struct SessionInfo {
 	/// The name of the session
 	pub name: String,
-	#[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnNull")]
 	#[serde(default)]
 	/// The description of the session
 	///
@@ -26,7 +26,7 @@ pub struct SessionInfo {
 	#[serde(rename = "normalizedSessionId")]
 	/// Normalized (capitalization) version of the session's id (`s-{uuid}` for
 	/// example)
-	pub normalized_id: String,
+	pub normalized_id: crate::id::Normalized<crate::id::Session>,
 	#[serde(rename = "hostUserId")]
 	/// The ID of the session's host (`U-{uuid}` for example)
 	pub host_id: Option<crate::id::User>,
@@ -42,14 +42,16 @@ pub struct SessionInfo {
 	/// If the host is a headless (server) instance or not.
 	pub is_headless_host: bool,
 	#[serde(rename = "sessionURLs")]
-	/// Links to the session, in custom protocols such as `lnl-nat:///` and
+	/// Links to the session, in custom protocols such as `lnl-nat://` and
 	/// `neos-steam://`
-	pub urls: Vec<String>,
+	pub urls: Vec<crate::model::SessionUrl>,
 	#[serde(rename = "sessionUsers")]
 	/// A list of the session's users very basic details.
 	#[cfg_attr(
 		not(feature = "debug"),
-		serde_as(as = "serde_with::VecSkipError<_>")
+		serde(
+			with = "serde_with::As::<crate::diagnostics::VecSkipError<serde_with::Same>>"
+		)
 	)]
 	pub users: Vec<crate::model::SessionUser>,
 	/// A link to the thumbnail of the session.
@@ -83,56 +85,52 @@ pub struct SessionInfo {
 	/// Who can access the session
 	pub access_level: crate::model::SessionAccessLevel,
 	/// If the session has ended
-	#[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnNull")]
 	#[serde(default = "has_ended_default")]
 	pub has_ended: bool,
 	/// If the session is valid
-	#[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnNull")]
 	#[serde(default)]
 	pub is_valid: bool,
-	#[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnNull")]
 	#[serde(default)]
 	/// Sessions that this session is a child of
 	///
 	/// Defaulted to empty vector if the API returns none for the session.
 	pub parent_session_ids: Vec<crate::id::Session>,
-	#[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnNull")]
 	#[serde(default)]
 	/// Sessions that are the child of this session
 	///
 	/// Defaulted to empty vector if the API returns none for the session.
 	pub nested_session_ids: Vec<crate::id::Session>,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 // If the field is missing, it probably has ended...
 const fn has_ended_default() -> bool { true }
 
-#[must_use]
-/// Tries to strip XML tags out of a string.
-///
-/// Not using an actual XML parser though, just a simple `<` and `>` character
-/// search.
-fn bad_xml_strip(str: &str) -> String {
-	let start_indexes = str.match_indices('<');
-	let end_indexes = str.match_indices('>');
+impl SessionInfo {
+	#[must_use]
+	/// Removes the rich text markup from a session's name.
+	pub fn stripped_name(&self) -> String { self.rich_name().plain_text() }
 
-	let mut stripped_name = str.to_owned();
-	start_indexes.rev().zip(end_indexes.rev()).for_each(
-		|((start, _), (end, _))| {
-			if start < end {
-				stripped_name.replace_range(start..=end, "");
-			}
-		},
-	);
+	#[must_use]
+	/// A `neos-session:///` link to join the session with
+	pub fn join_url(&self) -> crate::model::SessionUrl { self.id.clone().into() }
 
-	stripped_name
-}
+	#[must_use]
+	/// Parses the rich text markup of the session's name
+	pub fn rich_name(&self) -> crate::rich_text::RichText {
+		crate::rich_text::RichText::parse(&self.name)
+	}
 
-impl SessionInfo {
 	#[must_use]
-	/// Tries to remove the XML notation-like parts from a session's name.
-	///
-	/// Note that this is imperfect and not using an actual XML parser to remain
-	/// lightweight.
-	pub fn stripped_name(&self) -> String { bad_xml_strip(&self.name) }
+	/// Parses the rich text markup of the session's description
+	pub fn rich_description(&self) -> crate::rich_text::RichText {
+		crate::rich_text::RichText::parse(&self.description)
+	}
 }
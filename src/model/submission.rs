@@ -5,8 +5,11 @@ use time::{serde::rfc3339, OffsetDateTime};
 #[serde(rename_all = "camelCase")]
 /// A Neos record's submission to a group
 pub struct Submission {
-	#[serde(flatten)]
-	/// The id of the submission
+	/// The id of the submission.
+	///
+	/// A plain string, as the ID's prefix hasn't been confirmed yet, and a
+	/// wrong one would make the lenient [`crate::model::Record::submissions`]
+	/// silently drop every submission.
 	pub id: String,
 	/// The group that this submission is to
 	pub owner_id: crate::id::Group,
@@ -28,4 +31,45 @@ pub struct Submission {
 	#[serde(with = "crate::util::opt_rfc3339")]
 	/// When featuring this submission was enabled
 	pub featured_time: Option<OffsetDateTime>,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Submission {
+	#[must_use]
+	/// The ID of the submission, if it has the expected `SUB-` prefix
+	pub fn typed_id(&self) -> Option<crate::id::Submission> {
+		crate::id::Submission::try_from(self.id.clone()).ok()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Submission;
+
+	#[test]
+	fn any_id() {
+		let json = serde_json::json!({
+			"id": "unconfirmed-format",
+			"ownerId": "G-group",
+			"targetRecordId": "R-record",
+			"submissionTime": "2023-04-01T12:00:00Z",
+			"submittedById": "U-user",
+			"submittedByName": "user",
+			"featured": false,
+			"featuredByUserId": null,
+		});
+		let submission: Submission =
+			serde_json::from_value(json.clone()).expect("deserializing to work");
+		assert_eq!(submission.id, "unconfirmed-format");
+		assert!(submission.typed_id().is_none());
+
+		let mut json = json;
+		json["id"] = "SUB-submission".into();
+		let submission: Submission =
+			serde_json::from_value(json).expect("deserializing to work");
+		assert_eq!(submission.typed_id().unwrap().as_ref(), "SUB-submission");
+	}
 }
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 
 #[derive(
 	Debug,
@@ -6,16 +6,14 @@ use serde::{Deserialize, Serialize};
 	PartialEq,
 	Eq,
 	Hash,
-	Serialize,
-	Deserialize,
-	strum::FromRepr,
-	strum::Display,
+	SerializeDisplay,
+	DeserializeFromStr,
 	strum::EnumString,
-	strum::AsRefStr,
 	strum::EnumVariantNames,
 )]
-#[repr(u8)]
 /// A description of the type of transaction that happens with credits in Neos
+///
+/// Types that aren't known yet are kept as [`TransactionType::Other`].
 pub enum TransactionType {
 	/// An user sending monies to another user
 	User2User,
@@ -27,4 +25,41 @@ pub enum TransactionType {
 	Tip,
 	/// Exchanging monies for goods/services
 	Purchase,
+	#[strum(default)]
+	/// A type that isn't known to this crate yet
+	Other(String),
+}
+
+impl TransactionType {
+	#[must_use]
+	/// Creates a known transaction type from its numeric representation
+	pub const fn from_repr(repr: u8) -> Option<Self> {
+		Some(match repr {
+			0 => Self::User2User,
+			1 => Self::Withdrawal,
+			2 => Self::Deposit,
+			3 => Self::Tip,
+			4 => Self::Purchase,
+			_ => return None,
+		})
+	}
+}
+
+impl AsRef<str> for TransactionType {
+	fn as_ref(&self) -> &str {
+		match self {
+			Self::User2User => "User2User",
+			Self::Withdrawal => "Withdrawal",
+			Self::Deposit => "Deposit",
+			Self::Tip => "Tip",
+			Self::Purchase => "Purchase",
+			Self::Other(value) => value,
+		}
+	}
+}
+
+impl std::fmt::Display for TransactionType {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.as_ref())
+	}
 }
@@ -15,9 +15,9 @@ pub struct User {
 	/// The actual username
 	pub username: String,
 	/// Normalized (capitalization) version of the username.
-	pub normalized_username: String,
+	pub normalized_username: crate::id::Normalized<String>,
 	/// Possible alternatives to the normalized username
-	pub alternate_normalized_names: Option<Vec<String>>,
+	pub alternate_normalized_names: Option<Vec<crate::id::Normalized<String>>>,
 	/// The email address of the user. Only visible when logged in.
 	pub email: Option<String>,
 	#[serde(rename = "registrationDate")]
@@ -48,7 +48,7 @@ pub struct User {
 	#[serde(with = "crate::util::opt_rfc3339")]
 	/// When the listing ban expires
 	pub listing_ban_expiration: Option<OffsetDateTime>,
-	#[serde_as(deserialize_as = "serde_with::DefaultOnError")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnError")]
 	/// How much large is the users storage quota.
 	///
 	/// The API returns -1 for no permissions, which is de-serialized into None
@@ -58,7 +58,7 @@ pub struct User {
 	pub is_locked: bool,
 	/// If ban evasion is suppressed for the user.
 	pub supress_ban_evasion: bool,
-	#[serde_as(deserialize_as = "serde_with::DefaultOnError")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnError")]
 	/// How much storage quota the user has used.
 	///
 	/// The API returns -1 for no permissions, which is de-serialized into None
@@ -67,7 +67,7 @@ pub struct User {
 	#[serde(rename = "2fa_login")]
 	/// If the user has two factor authentication turned on.
 	pub two_factor_login: bool,
-	#[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+	#[serde_as(deserialize_as = "crate::diagnostics::DefaultOnNull")]
 	#[serde(default)]
 	/// Tags of the user. Seem to match up with the badges.
 	pub tags: Vec<String>,
@@ -82,4 +82,8 @@ pub struct User {
 	#[serde(rename = "NCRdepositAddress")]
 	/// NCR address, seems to exist only when authenticated.
 	pub ncr_deposit_address: Option<String>,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
 }
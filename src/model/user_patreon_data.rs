@@ -28,4 +28,8 @@ pub struct UserPatreonData {
 	/// Wrong/Invalid dates such as `0001-01-01T00:00:00` are expressed as
 	/// None.
 	pub last_activation_time: Option<OffsetDateTime>,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
 }
@@ -8,4 +8,8 @@ pub struct UserProfile {
 	pub icon_url: Option<crate::AssetUrl>,
 	/// If the user has opted out of "NCR" or "KCR" for example.
 	pub token_opt_out: Option<Vec<String>>,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
 }
@@ -12,7 +12,11 @@ use time::{serde::rfc3339, OffsetDateTime};
 pub struct UserSession {
 	/// The Neos user that this session is for
 	pub user_id: crate::id::User,
+	#[serde(default)]
 	/// The secret token of this session
+	///
+	/// Defaults to an empty String if none, as the API might leave it out when
+	/// listing the sessions.
 	pub token: String,
 	#[serde(rename = "created")]
 	#[serde(with = "rfc3339")]
@@ -40,6 +44,10 @@ pub struct UserSession {
 	pub e_tag: String,
 	/// Returned when creating a new session
 	pub secret_machine_id: Option<String>,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl UserSession {
@@ -48,12 +56,19 @@ impl UserSession {
 	pub fn auth_header(&self) -> String {
 		"neos ".to_owned() + self.user_id.as_ref() + ":" + &self.token
 	}
+
+	#[must_use]
+	/// If the session has expired by now
+	pub fn is_expired(&self) -> bool {
+		self.expiration <= OffsetDateTime::now_utc()
+	}
 }
 
 // Need to do manual impl to censor out secret token.
 impl std::fmt::Debug for UserSession {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		f.debug_struct("NeosUserSession")
+		let mut debug = f.debug_struct("NeosUserSession");
+		debug
 			.field("user_id", &self.user_id)
 			.field("token", &"*****")
 			.field(
@@ -68,7 +83,9 @@ impl std::fmt::Debug for UserSession {
 			.field("remember_me", &self.remember_me)
 			.field("source_ip", &self.source_ip)
 			.field("timestamp", &self.timestamp)
-			.field("e_tag", &self.e_tag)
-			.finish()
+			.field("e_tag", &self.e_tag);
+		// Only the keys, as unknown fields could be secret too
+		debug.field("extra", &self.extra.keys().collect::<Vec<_>>());
+		debug.finish()
 	}
 }
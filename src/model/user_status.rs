@@ -7,11 +7,8 @@ use time::OffsetDateTime;
 /// A Neos user's or friend's status.
 ///
 /// The response from the API at `users/{user_id}/status`.
-#[cfg_attr(
-	feature = "api_client",
-	doc = "Can be gotten with
-	[`Neos::get_user_status`](crate::api_client::Neos::get_user_status)"
-)]
+///
+/// Can be gotten with [`crate::query::UserStatus`].
 pub struct UserStatus {
 	/// "Online" / "Offline" and so on
 	pub online_status: crate::model::OnlineStatus,
@@ -44,12 +41,247 @@ pub struct UserStatus {
 	/// Only seems to exist when the user is online
 	#[cfg_attr(
 		not(feature = "debug"),
-		serde_as(as = "serde_with::VecSkipError<serde_with::DefaultOnNull>")
+		serde(
+			with = "serde_with::As::<crate::diagnostics::DefaultOnNull<crate::diagnostics::VecSkipError<serde_with::Same>>>"
+		)
 	)]
 	#[cfg_attr(
 		feature = "debug",
-		serde_as(deserialize_as = "serde_with::DefaultOnNull")
+		serde(with = "serde_with::As::<crate::diagnostics::DefaultOnNull>")
 	)]
 	#[serde(default)]
 	pub active_sessions: Vec<crate::model::SessionInfo>,
+	#[cfg_attr(feature = "extra_fields", serde(flatten))]
+	#[cfg_attr(not(feature = "extra_fields"), serde(skip))]
+	/// Unknown fields, see the [module docs](crate::model)
+	pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A change between two [`UserStatus`] snapshots.
+///
+/// Created with [`UserStatus::diff`].
+pub enum UserStatusChange {
+	/// The online status changed
+	OnlineStatus {
+		/// The previous value
+		from: crate::model::OnlineStatus,
+		/// The new value
+		to: crate::model::OnlineStatus,
+	},
+	/// The user moved to a different session
+	CurrentSession {
+		/// The previous value
+		from: Option<crate::id::Session>,
+		/// The new value
+		to: Option<crate::id::Session>,
+	},
+	/// The access level of the current session changed
+	CurrentSessionAccessLevel {
+		/// The previous value
+		from: crate::model::SessionAccessLevel,
+		/// The new value
+		to: crate::model::SessionAccessLevel,
+	},
+	/// The current session was hidden or un-hidden
+	CurrentSessionHidden {
+		/// The previous value
+		from: bool,
+		/// The new value
+		to: bool,
+	},
+	/// The user started or stopped hosting
+	CurrentHosting {
+		/// The previous value
+		from: bool,
+		/// The new value
+		to: bool,
+	},
+	/// The output device changed
+	OutputDevice {
+		/// The previous value
+		from: crate::model::OutputDevice,
+		/// The new value
+		to: crate::model::OutputDevice,
+	},
+	/// The compatibility hash changed
+	CompatibilityHash {
+		/// The previous value
+		from: Option<String>,
+		/// The new value
+		to: Option<String>,
+	},
+	/// The version of Neos changed
+	NeosVersion {
+		/// The previous value
+		from: Option<String>,
+		/// The new value
+		to: Option<String>,
+	},
+	/// The public RSA key changed
+	PublicRsaKey {
+		/// The previous value
+		from: Option<crate::model::RSAParametersData>,
+		/// The new value
+		to: Option<crate::model::RSAParametersData>,
+	},
+	/// The user switched between a mobile and a non-mobile client
+	IsMobile {
+		/// The previous value
+		from: bool,
+		/// The new value
+		to: bool,
+	},
+	/// A session appeared in the active sessions
+	SessionJoined(Box<crate::model::SessionInfo>),
+	/// A session disappeared from the active sessions
+	SessionLeft(Box<crate::model::SessionInfo>),
+}
+
+impl UserStatus {
+	#[must_use]
+	/// Compares this status to a newer one, listing what changed.
+	///
+	/// The last status change time is ignored, since it changes with any other
+	/// change anyway. Active sessions are compared by their IDs.
+	pub fn diff(&self, new: &Self) -> Vec<UserStatusChange> {
+		macro_rules! compare {
+			($changes:ident, $field:ident, $variant:ident) => {
+				if self.$field != new.$field {
+					$changes.push(UserStatusChange::$variant {
+						from: self.$field.clone(),
+						to: new.$field.clone(),
+					});
+				}
+			};
+		}
+
+		let mut changes = Vec::new();
+		compare!(changes, online_status, OnlineStatus);
+		compare!(changes, current_session_id, CurrentSession);
+		compare!(changes, current_session_access_level, CurrentSessionAccessLevel);
+		compare!(changes, is_current_session_hidden, CurrentSessionHidden);
+		compare!(changes, is_current_hosting, CurrentHosting);
+		compare!(changes, output_device, OutputDevice);
+		compare!(changes, compatibility_hash, CompatibilityHash);
+		compare!(changes, neos_version, NeosVersion);
+		compare!(changes, public_rsa_key, PublicRsaKey);
+		compare!(changes, is_mobile, IsMobile);
+
+		let has_session =
+			|sessions: &[crate::model::SessionInfo],
+			 session: &crate::model::SessionInfo| {
+				sessions.iter().any(|other| other.id == session.id)
+			};
+		changes.extend(
+			new
+				.active_sessions
+				.iter()
+				.filter(|session| !has_session(&self.active_sessions, session))
+				.map(|session| {
+					UserStatusChange::SessionJoined(Box::new(session.clone()))
+				}),
+		);
+		changes.extend(
+			self
+				.active_sessions
+				.iter()
+				.filter(|session| !has_session(&new.active_sessions, session))
+				.map(|session| {
+					UserStatusChange::SessionLeft(Box::new(session.clone()))
+				}),
+		);
+
+		changes
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{UserStatus, UserStatusChange};
+	use crate::model::OnlineStatus;
+
+	fn status(online_status: &str, neos_version: Option<&str>) -> UserStatus {
+		let mut json = crate::model::fixtures::user_status();
+		json["onlineStatus"] = online_status.into();
+		json["outputDevice"] = "Screen".into();
+		json["neosVersion"] = neos_version.into();
+		serde_json::from_value(json).expect("user status to deserialize")
+	}
+
+	#[test]
+	fn unchanged() {
+		let old = status("Online", Some("2022.1.28.1310"));
+		assert!(old.diff(&old.clone()).is_empty());
+	}
+
+	#[test]
+	fn online_and_version() {
+		let old = status("Offline", None);
+		let new = status("Online", Some("2022.1.28.1310"));
+		assert_eq!(
+			old.diff(&new),
+			vec![
+				UserStatusChange::OnlineStatus {
+					from: OnlineStatus::Offline,
+					to: OnlineStatus::Online
+				},
+				UserStatusChange::NeosVersion {
+					from: None,
+					to: Some("2022.1.28.1310".to_owned())
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn sessions_joined_and_left() {
+		let session = |id: &str, active_users: u8| {
+			let mut json = crate::model::fixtures::session_info();
+			json["sessionId"] = id.into();
+			json["activeUsers"] = active_users.into();
+			json
+		};
+		let with_sessions = |sessions: Vec<serde_json::Value>| {
+			let mut user_status = status("Online", None);
+			user_status.active_sessions =
+				serde_json::from_value(sessions.into()).expect("sessions to work");
+			user_status
+		};
+		let old = with_sessions(vec![session("S-a", 1), session("S-b", 1)]);
+		// Only the IDs matter, so the changed user count of S-b is not a change
+		let new = with_sessions(vec![session("S-b", 2), session("S-c", 1)]);
+
+		let changes = old.diff(&new);
+		assert_eq!(changes.len(), 2, "{changes:?}");
+		assert!(matches!(
+			&changes[0],
+			UserStatusChange::SessionJoined(session) if session.id.as_ref() == "S-c"
+		));
+		assert!(matches!(
+			&changes[1],
+			UserStatusChange::SessionLeft(session) if session.id.as_ref() == "S-a"
+		));
+	}
+
+	#[test]
+	fn invalid_active_sessions() {
+		let mut session = crate::model::fixtures::session_info();
+		session["sessionUsers"] = serde_json::json!([null]);
+		let mut json = crate::model::fixtures::user_status();
+		json["activeSessions"] = serde_json::json!([
+			null,
+			session,
+			crate::model::fixtures::session_info()
+		]);
+
+		let status = serde_json::from_value::<UserStatus>(json);
+		if cfg!(feature = "debug") {
+			assert!(status.is_err());
+		} else {
+			let status = status.expect("invalid entries to be skipped");
+			assert_eq!(status.active_sessions.len(), 2);
+			assert!(status.active_sessions.iter().all(|s| s.users.is_empty()));
+		}
+	}
 }
@@ -0,0 +1,268 @@
+//! Tracking the presence of friends by polling them.
+//!
+//! The [`PresenceTracker`] keeps the latest known details of each friend, and
+//! turns new [`Friends`](crate::query::Friends) query results into
+//! [`PresenceEvent`]s.
+//!
+//! # Example usage
+//!
+//! ```
+//! # use neos::presence::{PresenceEvent, PresenceTracker};
+//! let mut tracker = PresenceTracker::default();
+//! // Normally you'd get the friends by querying the API with `tracker.query()`
+//! let friends: Vec<neos::model::Friend> = Vec::new();
+//! for event in tracker.update(friends) {
+//! 	if let PresenceEvent::CameOnline { id, .. } = event {
+//! 		println!("{} came online", id.as_ref());
+//! 	}
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use time::OffsetDateTime;
+
+use crate::model::{Friend, OnlineStatus, OutputDevice, UserStatusChange};
+
+/// A change in the presence of a friend
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PresenceEvent {
+	/// A friend that wasn't known before was seen
+	Added(Box<Friend>),
+	/// The friend went from offline or invisible to being online
+	CameOnline {
+		/// The ID of the friend
+		id: crate::id::User,
+		/// The new online status, such as online or away
+		status: OnlineStatus,
+	},
+	/// The friend went offline or invisible
+	WentOffline {
+		/// The ID of the friend
+		id: crate::id::User,
+	},
+	/// The online status changed, while staying online
+	StatusChanged {
+		/// The ID of the friend
+		id: crate::id::User,
+		/// The previous online status
+		from: OnlineStatus,
+		/// The new online status
+		to: OnlineStatus,
+	},
+	/// The friend moved to a different session
+	SessionChanged {
+		/// The ID of the friend
+		id: crate::id::User,
+		/// The previous session, if any
+		from: Option<crate::id::Session>,
+		/// The new session, if any
+		to: Option<crate::id::Session>,
+	},
+	/// The friend started using a different output device
+	OutputDeviceChanged {
+		/// The ID of the friend
+		id: crate::id::User,
+		/// The previous output device
+		from: OutputDevice,
+		/// The new output device
+		to: OutputDevice,
+	},
+	/// The friend started hosting a session
+	StartedHosting {
+		/// The ID of the friend
+		id: crate::id::User,
+	},
+	/// The friend stopped hosting a session
+	StoppedHosting {
+		/// The ID of the friend
+		id: crate::id::User,
+	},
+}
+
+impl PresenceEvent {
+	#[must_use]
+	/// The ID of the friend that the event is about
+	pub const fn id(&self) -> &crate::id::User {
+		match self {
+			Self::Added(friend) => &friend.id,
+			Self::CameOnline { id, .. }
+			| Self::WentOffline { id }
+			| Self::StatusChanged { id, .. }
+			| Self::SessionChanged { id, .. }
+			| Self::OutputDeviceChanged { id, .. }
+			| Self::StartedHosting { id }
+			| Self::StoppedHosting { id } => id,
+		}
+	}
+}
+
+/// Keeps track of the friends' latest details
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PresenceTracker {
+	friends: HashMap<crate::id::User, Friend>,
+	last_status_update: Option<OffsetDateTime>,
+}
+
+impl PresenceTracker {
+	#[must_use]
+	/// The latest known details of the friends
+	pub const fn friends(&self) -> &HashMap<crate::id::User, Friend> {
+		&self.friends
+	}
+
+	#[must_use]
+	/// The latest known details of a friend
+	pub fn friend(&self, id: &crate::id::User) -> Option<&Friend> {
+		self.friends.get(id)
+	}
+
+	#[must_use]
+	/// A query for the friends with status updates since the last one seen
+	pub const fn query(&self) -> crate::query::Friends {
+		crate::query::Friends { last_status_update: self.last_status_update }
+	}
+
+	/// Updates the tracked friends, returning what changed
+	pub fn update(
+		&mut self, friends: impl IntoIterator<Item = Friend>,
+	) -> Vec<PresenceEvent> {
+		let mut events = Vec::new();
+
+		for friend in friends {
+			if let Some(time) = friend.status.last_status_change_time {
+				if self.last_status_update.is_none_or(|last| last < time) {
+					self.last_status_update = Some(time);
+				}
+			}
+
+			match self.friends.get(&friend.id) {
+				Some(old) => diff(old, &friend, &mut events),
+				None => events.push(PresenceEvent::Added(Box::new(friend.clone()))),
+			}
+			self.friends.insert(friend.id.clone(), friend);
+		}
+
+		events
+	}
+
+	#[cfg(feature = "http_client")]
+	/// Queries the friends that have changed and updates the tracked ones
+	///
+	/// # Errors
+	///
+	/// If querying the friends fails
+	pub async fn poll(
+		&mut self, client: &crate::api_client::AuthenticatedNeos,
+	) -> Result<Vec<PresenceEvent>, crate::api_client::ApiError> {
+		use crate::api_client::ApiClient;
+
+		let friends = client.query(self.query()).await?;
+		Ok(self.update(friends))
+	}
+}
+
+const fn is_online(status: &OnlineStatus) -> bool {
+	!matches!(status, OnlineStatus::Offline | OnlineStatus::Invisible)
+}
+
+fn diff(old: &Friend, new: &Friend, events: &mut Vec<PresenceEvent>) {
+	let id = || new.id.clone();
+
+	events.extend(old.status.diff(&new.status).into_iter().filter_map(
+		|change| {
+			Some(match change {
+				UserStatusChange::OnlineStatus { from, to } => {
+					match (is_online(&from), is_online(&to)) {
+						(false, true) => PresenceEvent::CameOnline { id: id(), status: to },
+						(true, false) => PresenceEvent::WentOffline { id: id() },
+						_ => PresenceEvent::StatusChanged { id: id(), from, to },
+					}
+				}
+				UserStatusChange::CurrentSession { from, to } => {
+					PresenceEvent::SessionChanged { id: id(), from, to }
+				}
+				UserStatusChange::OutputDevice { from, to } => {
+					PresenceEvent::OutputDeviceChanged { id: id(), from, to }
+				}
+				UserStatusChange::CurrentHosting { to: true, .. } => {
+					PresenceEvent::StartedHosting { id: id() }
+				}
+				UserStatusChange::CurrentHosting { to: false, .. } => {
+					PresenceEvent::StoppedHosting { id: id() }
+				}
+				_ => return None,
+			})
+		},
+	));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{PresenceEvent, PresenceTracker};
+	use crate::model::{Friend, OnlineStatus, OutputDevice};
+
+	fn friend(status: &str, session: Option<&str>, hosting: bool) -> Friend {
+		let mut json = crate::model::fixtures::friend();
+		let user_status = &mut json["userStatus"];
+		user_status["onlineStatus"] = status.into();
+		user_status["lastStatusChange"] = "2023-04-02T08:30:00Z".into();
+		user_status["currentSessionId"] = session.into();
+		user_status["currentHosting"] = hosting.into();
+		user_status["outputDevice"] = "VR".into();
+		serde_json::from_value(json).expect("friend to deserialize")
+	}
+
+	#[test]
+	fn first_seen() {
+		let mut tracker = PresenceTracker::default();
+		let events = tracker.update([friend("Online", None, false)]);
+		assert!(matches!(events.as_slice(), [PresenceEvent::Added(_)]));
+		assert!(tracker.query().last_status_update.is_some());
+	}
+
+	#[test]
+	fn came_online_and_started_hosting() {
+		let mut tracker = PresenceTracker::default();
+		tracker.update([friend("Offline", None, false)]);
+		let events = tracker.update([friend("Online", Some("S-session"), true)]);
+		let id = crate::id::User::try_from("U-friend").unwrap();
+		assert_eq!(
+			events,
+			vec![
+				PresenceEvent::CameOnline {
+					id: id.clone(),
+					status: OnlineStatus::Online
+				},
+				PresenceEvent::SessionChanged {
+					id: id.clone(),
+					from: None,
+					to: Some(crate::id::Session::try_from("S-session").unwrap()),
+				},
+				PresenceEvent::StartedHosting { id },
+			]
+		);
+	}
+
+	#[test]
+	fn output_device_and_offline() {
+		let mut tracker = PresenceTracker::default();
+		tracker.update([friend("Away", None, false)]);
+		let mut new = friend("Invisible", None, false);
+		new.status.output_device = OutputDevice::Screen;
+		let events = tracker.update([new]);
+		assert_eq!(events.len(), 2);
+		assert!(matches!(events[0], PresenceEvent::WentOffline { .. }));
+		assert!(matches!(
+			events[1],
+			PresenceEvent::OutputDeviceChanged { to: OutputDevice::Screen, .. }
+		));
+	}
+
+	#[test]
+	fn unchanged() {
+		let mut tracker = PresenceTracker::default();
+		tracker.update([friend("Busy", None, false)]);
+		assert!(tracker.update([friend("Busy", None, false)]).is_empty());
+	}
+}
@@ -0,0 +1,287 @@
+use racal::Queryable;
+use serde::{Deserialize, Serialize};
+
+use super::{Authentication, NoAuthentication};
+
+/// Registers a new user account
+///
+/// # Example usage
+///
+/// ```
+/// # use neos::query::Register;
+/// let register = Register::new("NewUser", "user@example.com", "Secret123");
+/// assert!(register.is_ok());
+/// assert!(Register::new("NewUser", "user@example.com", "secret").is_err());
+/// assert!(Register::new(" ", "user@example.com", "Secret123").is_err());
+/// ```
+///
+/// The fields are private so that the query can only be created with a valid
+/// username and password.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Register {
+	username: String,
+	email: String,
+	password: String,
+}
+
+impl Register {
+	/// Creates a new registration query
+	///
+	/// # Errors
+	///
+	/// If the username or password isn't valid, see
+	/// [`Register::validate_username`] and [`Register::validate_password`]
+	pub fn new(
+		username: impl Into<String>, email: impl Into<String>,
+		password: impl Into<String>,
+	) -> Result<Self, &'static str> {
+		let register = Self {
+			username: username.into(),
+			email: email.into(),
+			password: password.into(),
+		};
+		Self::validate_username(&register.username)?;
+		Self::validate_password(&register.password)?;
+		if !register.email.contains('@') {
+			return Err("email should be an email address");
+		}
+		Ok(register)
+	}
+
+	#[must_use]
+	/// The username of the new account
+	pub fn username(&self) -> &str { &self.username }
+
+	#[must_use]
+	/// The email address of the new account, which needs to be verified
+	pub fn email(&self) -> &str { &self.email }
+
+	/// Checks that the username follows Neos' rules.
+	///
+	/// # Errors
+	///
+	/// If the username isn't between 3 and 32 characters long, has whitespace
+	/// around it or contains control characters
+	pub fn validate_username(username: &str) -> Result<(), &'static str> {
+		if !(3..=32).contains(&username.chars().count()) {
+			return Err("username should be between 3 and 32 characters long");
+		}
+		if username.trim() != username {
+			return Err("username shouldn't start or end with whitespace");
+		}
+		if username.chars().any(char::is_control) {
+			return Err("username shouldn't contain control characters");
+		}
+		Ok(())
+	}
+
+	/// Checks that the password follows Neos' rules.
+	///
+	/// # Errors
+	///
+	/// If the password isn't at least 8 characters long, with at least one
+	/// uppercase letter, lowercase letter and digit
+	pub fn validate_password(password: &str) -> Result<(), &'static str> {
+		if password.chars().count() < 8 {
+			return Err("password should be at least 8 characters long");
+		}
+		if !password.chars().any(char::is_uppercase)
+			|| !password.chars().any(char::is_lowercase)
+			|| !password.chars().any(|c| c.is_ascii_digit())
+		{
+			return Err(
+				"password should contain an uppercase letter, a lowercase letter and \
+				 a digit",
+			);
+		}
+		Ok(())
+	}
+}
+
+impl Queryable<NoAuthentication, crate::model::User> for Register {
+	fn url(&self, _: &NoAuthentication) -> String {
+		format!("{}/users", crate::API_BASE_URI)
+	}
+
+	fn body(&self, _: &NoAuthentication) -> Option<serde_json::Result<Vec<u8>>> {
+		Some(serde_json::to_vec(self))
+	}
+
+	fn method(&self, _: &NoAuthentication) -> racal::RequestMethod {
+		racal::RequestMethod::Post
+	}
+}
+
+impl std::fmt::Debug for Register {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Register")
+			.field("username", &self.username)
+			.field("email", &self.email)
+			.field("password", &"*****")
+			.finish()
+	}
+}
+
+/// Changes the password of the logged in user
+///
+/// The fields are private so that the query can only be created with a valid
+/// new password.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePassword {
+	current_password: String,
+	#[serde(rename = "password")]
+	new_password: String,
+}
+
+impl ChangePassword {
+	/// Creates a new password change query
+	///
+	/// # Errors
+	///
+	/// If the new password isn't valid, see [`Register::validate_password`]
+	pub fn new(
+		current_password: impl Into<String>, new_password: impl Into<String>,
+	) -> Result<Self, &'static str> {
+		let new_password = new_password.into();
+		Register::validate_password(&new_password)?;
+		Ok(Self { current_password: current_password.into(), new_password })
+	}
+}
+
+impl Queryable<Authentication, ()> for ChangePassword {
+	fn url(&self, auth: &Authentication) -> String {
+		format!("{}/users/{}", crate::API_BASE_URI, auth.user_id.as_ref())
+	}
+
+	fn body(&self, _: &Authentication) -> Option<serde_json::Result<Vec<u8>>> {
+		Some(serde_json::to_vec(self))
+	}
+
+	fn method(&self, _: &Authentication) -> racal::RequestMethod {
+		racal::RequestMethod::Patch
+	}
+
+	fn deserialize(&self, _data: &[u8]) -> serde_json::Result<()> { Ok(()) }
+}
+
+impl std::fmt::Debug for ChangePassword {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("ChangePassword")
+			.field("current_password", &"*****")
+			.field("new_password", &"*****")
+			.finish()
+	}
+}
+
+/// Requests an email for resetting a forgotten password
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RequestPasswordReset {
+	/// The email address of the account
+	pub email: String,
+}
+
+impl RequestPasswordReset {
+	/// Creates a new password reset request query
+	pub fn new(email: impl Into<String>) -> Self { Self { email: email.into() } }
+}
+
+impl Queryable<NoAuthentication, ()> for RequestPasswordReset {
+	fn url(&self, _: &NoAuthentication) -> String {
+		format!("{}/users/requestLostPassword", crate::API_BASE_URI)
+	}
+
+	fn body(&self, _: &NoAuthentication) -> Option<serde_json::Result<Vec<u8>>> {
+		Some(serde_json::to_vec(self))
+	}
+
+	fn method(&self, _: &NoAuthentication) -> racal::RequestMethod {
+		racal::RequestMethod::Post
+	}
+
+	fn deserialize(&self, _data: &[u8]) -> serde_json::Result<()> { Ok(()) }
+}
+
+/// Sends the email for verifying the logged in user's email address again
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResendVerification;
+
+impl Queryable<Authentication, ()> for ResendVerification {
+	fn url(&self, auth: &Authentication) -> String {
+		format!(
+			"{}/users/{}/requestVerification",
+			crate::API_BASE_URI,
+			auth.user_id.as_ref()
+		)
+	}
+
+	fn method(&self, _: &Authentication) -> racal::RequestMethod {
+		racal::RequestMethod::Post
+	}
+
+	fn deserialize(&self, _data: &[u8]) -> serde_json::Result<()> { Ok(()) }
+}
+
+/// Updates the profile of the logged in user
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct UpdateProfile {
+	/// The profile that replaces the current one
+	pub profile: crate::model::UserProfile,
+}
+
+impl UpdateProfile {
+	#[must_use]
+	/// Creates a new profile update query
+	pub const fn new(profile: crate::model::UserProfile) -> Self {
+		Self { profile }
+	}
+}
+
+impl Queryable<Authentication, ()> for UpdateProfile {
+	fn url(&self, auth: &Authentication) -> String {
+		format!("{}/users/{}/profile", crate::API_BASE_URI, auth.user_id.as_ref())
+	}
+
+	fn body(&self, _: &Authentication) -> Option<serde_json::Result<Vec<u8>>> {
+		Some(serde_json::to_vec(&self.profile))
+	}
+
+	fn method(&self, _: &Authentication) -> racal::RequestMethod {
+		racal::RequestMethod::Put
+	}
+
+	fn deserialize(&self, _data: &[u8]) -> serde_json::Result<()> { Ok(()) }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ChangePassword, Register};
+
+	#[test]
+	fn username_rules() {
+		assert!(Register::validate_username("Neos").is_ok());
+		assert!(Register::validate_username("ab").is_err());
+		assert!(Register::validate_username(&"a".repeat(33)).is_err());
+		assert!(Register::validate_username(" Neos").is_err());
+		assert!(Register::validate_username("Ne\nos").is_err());
+	}
+
+	#[test]
+	fn password_rules() {
+		assert!(Register::validate_password("Secret123").is_ok());
+		assert!(Register::validate_password("Sec123").is_err());
+		assert!(Register::validate_password("secret123").is_err());
+		assert!(Register::validate_password("SECRET123").is_err());
+		assert!(Register::validate_password("SecretPassword").is_err());
+		assert!(ChangePassword::new("old", "short").is_err());
+	}
+
+	#[test]
+	fn redacted() {
+		let query = ChangePassword::new("Old123456", "Secret123").unwrap();
+		assert!(!format!("{query:?}").contains("Secret123"));
+		let json = serde_json::to_value(&query).unwrap();
+		assert_eq!(json["password"], "Secret123");
+		assert_eq!(json["currentPassword"], "Old123456");
+	}
+}
@@ -0,0 +1,216 @@
+use racal::Queryable;
+use serde::{Deserialize, Serialize};
+
+use super::Authentication;
+use crate::model::{CreditTransaction, TransactionType};
+
+/// Gets the credit transactions of the logged in user
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Transactions {
+	/// The name of the token to get the transactions of ("NCR" for example)
+	pub token: String,
+}
+
+impl Transactions {
+	/// Creates a new transactions query for the token
+	pub fn new(token: impl Into<String>) -> Self { Self { token: token.into() } }
+}
+
+impl Default for Transactions {
+	/// Creates a transactions query for NCR
+	fn default() -> Self { Self::new("NCR") }
+}
+
+impl Queryable<Authentication, Vec<CreditTransaction>> for Transactions {
+	fn url(&self, auth: &Authentication) -> String {
+		format!(
+			"{}/users/{}/transactions/{}",
+			crate::API_BASE_URI,
+			auth.user_id.as_ref(),
+			crate::util::encode_path_segment(&self.token)
+		)
+	}
+}
+
+/// A transfer of credits to another user.
+///
+/// Neos transfers credits by sending a message to the recipient, which can be
+/// created with [`CreditTransfer::message`] and then sent like any other
+/// message.
+///
+/// # Example usage
+///
+/// ```
+/// # use neos::query::CreditTransfer;
+/// let recipient = neos::id::User::try_from("U-Neos").unwrap();
+/// let transfer = CreditTransfer::new(recipient, "NCR", 2.5)
+/// 	.unwrap()
+/// 	.tip()
+/// 	.comment("Thanks for the world!");
+/// assert_eq!(transfer.amount(), 2.5);
+/// assert!(CreditTransfer::new(transfer.to().clone(), "NCR", -1.0).is_err());
+/// ```
+///
+/// The fields are private so that the transfer can only be created with a
+/// valid amount and transaction type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreditTransfer {
+	to: crate::id::User,
+	token: String,
+	amount: f64,
+	transaction_type: TransactionType,
+	comment: String,
+	anonymous: bool,
+}
+
+impl CreditTransfer {
+	/// Creates a new transfer between users
+	///
+	/// # Errors
+	///
+	/// If the amount isn't valid, see [`CreditTransfer::validate_amount`]
+	pub fn new(
+		to: crate::id::User, token: impl Into<String>, amount: f64,
+	) -> Result<Self, &'static str> {
+		Self::validate_amount(amount)?;
+		Ok(Self {
+			to,
+			token: token.into(),
+			amount,
+			transaction_type: TransactionType::User2User,
+			comment: String::new(),
+			anonymous: false,
+		})
+	}
+
+	/// Checks that the amount can be transferred.
+	///
+	/// # Errors
+	///
+	/// If the amount isn't a positive finite number
+	pub fn validate_amount(amount: f64) -> Result<(), &'static str> {
+		if !amount.is_finite() {
+			return Err("amount should be a finite number");
+		}
+		if amount <= 0.0 {
+			return Err("amount should be more than zero");
+		}
+		Ok(())
+	}
+
+	#[must_use]
+	/// Who receives the credits
+	pub const fn to(&self) -> &crate::id::User { &self.to }
+
+	#[must_use]
+	/// The name of the token ("NCR" for example)
+	pub fn token(&self) -> &str { &self.token }
+
+	#[must_use]
+	/// How much of the token is transferred
+	pub const fn amount(&self) -> f64 { self.amount }
+
+	#[must_use]
+	/// Either [`TransactionType::User2User`] or [`TransactionType::Tip`]
+	pub const fn transaction_type(&self) -> &TransactionType {
+		&self.transaction_type
+	}
+
+	#[must_use]
+	/// A message attached to the transfer
+	pub fn comment_text(&self) -> &str { &self.comment }
+
+	#[must_use]
+	/// If the sender is hidden from the recipient
+	pub const fn is_anonymous(&self) -> bool { self.anonymous }
+
+	#[must_use]
+	/// Makes the transfer a tip
+	pub fn tip(mut self) -> Self {
+		self.transaction_type = TransactionType::Tip;
+		self
+	}
+
+	#[must_use]
+	/// Sets the comment of the transfer
+	pub fn comment(mut self, comment: impl Into<String>) -> Self {
+		self.comment = comment.into();
+		self
+	}
+
+	#[must_use]
+	/// Sets the anonymous field's value
+	pub const fn anonymous(mut self, anonymous: bool) -> Self {
+		self.anonymous = anonymous;
+		self
+	}
+
+	#[must_use]
+	/// The transaction that is sent in the message
+	pub fn transaction(&self, sender: &crate::id::User) -> CreditTransaction {
+		CreditTransaction {
+			token: self.token.clone(),
+			from_user_id: Some(sender.clone()),
+			amount: self.amount,
+			transaction_type: self.transaction_type.clone(),
+			comment: self.comment.clone(),
+			anonymous: self.anonymous,
+			extra: serde_json::Map::new(),
+		}
+	}
+
+	#[cfg(feature = "rand_util")]
+	#[must_use]
+	/// Creates the message that makes the transfer when sent
+	pub fn message(&self, sender: crate::id::User) -> crate::model::Message {
+		crate::model::Message::new(
+			crate::model::MessageContents::CreditTransfer(self.transaction(&sender)),
+			sender,
+			self.to.clone(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::CreditTransfer;
+
+	#[test]
+	fn transactions_url() {
+		use racal::Queryable;
+
+		let auth = crate::query::Authentication {
+			token: String::new(),
+			user_id: crate::id::User::try_from("U-user").unwrap(),
+		};
+		assert_eq!(
+			super::Transactions::new("K C/R?").url(&auth),
+			format!("{}/users/U-user/transactions/K%20C%2FR%3F", crate::API_BASE_URI)
+		);
+	}
+
+	#[test]
+	fn amount_validation() {
+		let to = crate::id::User::try_from("U-Neos").unwrap();
+		assert!(CreditTransfer::new(to.clone(), "NCR", 0.5).is_ok());
+		assert!(CreditTransfer::new(to.clone(), "NCR", 0.0).is_err());
+		assert!(CreditTransfer::new(to.clone(), "NCR", -2.0).is_err());
+		assert!(CreditTransfer::new(to, "NCR", f64::NAN).is_err());
+	}
+
+	#[cfg(feature = "rand_util")]
+	#[test]
+	fn message() {
+		let to = crate::id::User::try_from("U-Neos").unwrap();
+		let sender = crate::id::User::try_from("U-sender").unwrap();
+		let message =
+			CreditTransfer::new(to, "NCR", 1.0).unwrap().tip().message(sender);
+		let json = serde_json::to_value(&message).unwrap();
+		assert_eq!(json["messageType"], "CreditTransfer");
+		assert_eq!(json["recipientId"], "U-Neos");
+		let content: serde_json::Value =
+			serde_json::from_str(json["content"].as_str().unwrap()).unwrap();
+		assert_eq!(content["transactionType"], "Tip");
+		assert_eq!(content["fromUserId"], "U-sender");
+	}
+}
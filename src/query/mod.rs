@@ -5,6 +5,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::model::UserSession;
 
+mod account;
+pub use account::*;
+mod credits;
+pub use credits::*;
 mod friends;
 pub use friends::*;
 mod groups;
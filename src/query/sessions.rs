@@ -4,12 +4,126 @@ use serde::{Deserialize, Serialize};
 use super::NoAuthentication;
 
 /// Gets details of publicly listed sessions
-pub struct Sessions;
+///
+/// The parameters are filtered by the API, with every parameter left as none
+/// listing all of the sessions.
+///
+/// # Example usage
+///
+/// ```
+/// use neos::query::Sessions;
+///
+/// let query = Sessions::default().name("Neos Hub").min_active_users(1);
+/// ```
+#[derive(
+	Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Sessions {
+	/// Only list sessions with this compatibility hash
+	pub compatibility_hash: Option<String>,
+	/// Only list sessions with names that contain this
+	pub name: Option<String>,
+	/// Only list sessions of this universe
+	pub universe_id: Option<String>,
+	/// Only list sessions hosted by an user with this username
+	pub host_name: Option<String>,
+	/// Only list sessions hosted by this user
+	pub host_id: Option<crate::id::User>,
+	/// Only list sessions with at least this many active users
+	pub min_active_users: Option<u8>,
+	/// If headless sessions without users should be listed
+	pub include_empty_headless: Option<bool>,
+}
+
+impl Sessions {
+	#[must_use]
+	/// Sets the compatibility hash
+	pub fn compatibility_hash(
+		mut self, compatibility_hash: impl Into<String>,
+	) -> Self {
+		self.compatibility_hash = Some(compatibility_hash.into());
+		self
+	}
+
+	#[must_use]
+	/// Sets the name
+	pub fn name(mut self, name: impl Into<String>) -> Self {
+		self.name = Some(name.into());
+		self
+	}
+
+	#[must_use]
+	/// Sets the universe ID
+	pub fn universe_id(mut self, universe_id: impl Into<String>) -> Self {
+		self.universe_id = Some(universe_id.into());
+		self
+	}
+
+	#[must_use]
+	/// Sets the host's username
+	pub fn host_name(mut self, host_name: impl Into<String>) -> Self {
+		self.host_name = Some(host_name.into());
+		self
+	}
+
+	#[must_use]
+	/// Sets the host's ID
+	pub fn host_id(mut self, host_id: impl Into<crate::id::User>) -> Self {
+		self.host_id = Some(host_id.into());
+		self
+	}
+
+	#[must_use]
+	/// Sets the least amount of active users
+	pub const fn min_active_users(mut self, min_active_users: u8) -> Self {
+		self.min_active_users = Some(min_active_users);
+		self
+	}
+
+	#[must_use]
+	/// Sets if empty headless sessions are included
+	pub const fn include_empty_headless(
+		mut self, include_empty_headless: bool,
+	) -> Self {
+		self.include_empty_headless = Some(include_empty_headless);
+		self
+	}
+}
 
 // TODO: VecSkipError
 impl Queryable<NoAuthentication, Vec<crate::model::SessionInfo>> for Sessions {
 	fn url(&self, _: &NoAuthentication) -> String {
-		format!("{}/sessions", crate::API_BASE_URI)
+		let mut params = form_urlencoded::Serializer::new(String::new());
+
+		let string_params = [
+			("compatibilityHash", self.compatibility_hash.as_deref()),
+			("name", self.name.as_deref()),
+			("universeId", self.universe_id.as_deref()),
+			("hostName", self.host_name.as_deref()),
+			("hostId", self.host_id.as_ref().map(AsRef::as_ref)),
+		];
+		for (key, value) in string_params {
+			if let Some(value) = value {
+				params.append_pair(key, value);
+			}
+		}
+		if let Some(min_active_users) = self.min_active_users {
+			params.append_pair("minActiveUsers", &min_active_users.to_string());
+		}
+		if let Some(include_empty_headless) = self.include_empty_headless {
+			params.append_pair(
+				"includeEmptyHeadless",
+				&include_empty_headless.to_string(),
+			);
+		}
+
+		let params = params.finish();
+		if params.is_empty() {
+			format!("{}/sessions", crate::API_BASE_URI)
+		} else {
+			format!("{}/sessions?{params}", crate::API_BASE_URI)
+		}
 	}
 }
 
@@ -32,3 +146,38 @@ impl Queryable<NoAuthentication, crate::model::SessionInfo> for SessionInfo {
 		format!("{}/sessions/{}", crate::API_BASE_URI, self.session_id.as_ref())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use racal::Queryable;
+
+	use super::{NoAuthentication, Sessions};
+
+	#[test]
+	fn sessions_url() {
+		let url = |query: Sessions| {
+			Queryable::<_, Vec<crate::model::SessionInfo>>::url(
+				&query,
+				&NoAuthentication {},
+			)
+		};
+
+		assert_eq!(
+			url(Sessions::default()),
+			format!("{}/sessions", crate::API_BASE_URI)
+		);
+		assert_eq!(
+			url(
+				Sessions::default()
+					.name("Café & <b>Chill</b>")
+					.host_id(crate::id::User::try_from("U-some user").unwrap())
+					.min_active_users(2)
+					.include_empty_headless(false)
+			),
+			format!(
+				"{}/sessions?name=Caf%C3%A9+%26+%3Cb%3EChill%3C%2Fb%3E&hostId=U-some+user&minActiveUsers=2&includeEmptyHeadless=false",
+				crate::API_BASE_URI
+			)
+		);
+	}
+}
@@ -65,10 +65,12 @@ impl LoginCredentials {
 
 	#[cfg(feature = "rand_util")]
 	#[must_use]
-	/// Sets the `machine_id` to a not cryptographically safe generated
-	/// pseudorandom value.
+	/// Sets the `machine_id` to a generated random value.
+	///
+	/// Enable the `secure_rand` feature for it to be cryptographically safe, as
+	/// it's a secret that protects the login session.
 	pub fn use_generated_machine_id(mut self) -> Self {
-		self.secret_machine_id = Some(crate::util::random_ascii_string(32));
+		self.secret_machine_id = Some(crate::util::random_machine_id());
 		self
 	}
 }
@@ -143,7 +145,7 @@ impl LoginCredentialsIdentifier {
 
 	#[must_use]
 	/// Gets the inner string
-	pub fn inner_mut(&mut self) -> &mut String {
+	pub const fn inner_mut(&mut self) -> &mut String {
 		match self {
 			Self::Username(s) | Self::Email(s) | Self::OwnerID(s) => s,
 		}
@@ -191,3 +193,139 @@ impl Queryable<Authentication, ()> for ExtendUserSession {
 
 	fn deserialize(&self, _data: &[u8]) -> serde_json::Result<()> { Ok(()) }
 }
+
+/// Lists the login sessions of the logged in user's account
+///
+/// # Example usage
+///
+/// ```no_run
+/// # #[cfg(feature = "http_client")]
+/// # tokio_test::block_on(async {
+/// # use neos::{api_client::{AuthenticatedNeos, ApiClient}, query::{UserSessions, RevokeUserSession}};
+/// # let neos_api_client: AuthenticatedNeos = todo!();
+/// let sessions = neos_api_client.query(UserSessions).await.unwrap();
+/// for session in &sessions {
+/// 	println!("{} from {}", session.creation_time, session.source_ip);
+/// }
+/// for revoke in RevokeUserSession::all_others(&sessions, neos_api_client.state()) {
+/// 	match revoke {
+/// 		Ok(revoke) => neos_api_client.query(revoke).await.unwrap(),
+/// 		Err(session) => println!("Can't revoke {} logins", session.source_ip),
+/// 	}
+/// }
+/// # })
+/// ```
+pub struct UserSessions;
+
+impl Queryable<Authentication, Vec<UserSession>> for UserSessions {
+	fn url(&self, _: &Authentication) -> String {
+		format!("{}/userSessions", crate::API_BASE_URI)
+	}
+}
+
+/// Revokes a login session of the logged in user's account, logging it out.
+///
+/// The API doesn't expose any other identifier for the sessions than their
+/// secret token, so it's a part of the URL. The API clients redact it from
+/// their tracing spans and recorded interactions.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct RevokeUserSession {
+	token: String,
+}
+
+impl RevokeUserSession {
+	/// Creates a new query for revoking the session
+	///
+	/// # Errors
+	///
+	/// If the session's token is missing, like it might be when listing them
+	pub fn new(session: &UserSession) -> Result<Self, &'static str> {
+		if session.token.is_empty() {
+			return Err("the session's token is needed for revoking it");
+		}
+		Ok(Self { token: session.token.clone() })
+	}
+
+	#[must_use]
+	/// Creates queries for revoking all of the sessions except the one that's
+	/// currently authenticated with.
+	///
+	/// Sessions that can't be revoked since their token is missing are returned
+	/// as errors. Note that the current session can be one of them too, as
+	/// without a token it can't be told apart from the others.
+	pub fn all_others<'a>(
+		sessions: &'a [UserSession], auth: &Authentication,
+	) -> Vec<Result<Self, &'a UserSession>> {
+		sessions
+			.iter()
+			.filter(|session| session.token != auth.token)
+			.map(|session| Self::new(session).map_err(|_| session))
+			.collect()
+	}
+}
+
+impl Queryable<Authentication, ()> for RevokeUserSession {
+	fn url(&self, auth: &Authentication) -> String {
+		format!(
+			"{}/userSessions/{}/{}",
+			crate::API_BASE_URI,
+			auth.user_id.as_ref(),
+			crate::util::encode_path_segment(&self.token)
+		)
+	}
+
+	fn method(&self, _: &Authentication) -> racal::RequestMethod {
+		racal::RequestMethod::Delete
+	}
+
+	fn deserialize(&self, _data: &[u8]) -> serde_json::Result<()> { Ok(()) }
+}
+
+impl std::fmt::Debug for RevokeUserSession {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("RevokeUserSession").field("token", &"*****").finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use racal::Queryable;
+
+	use super::{Authentication, RevokeUserSession};
+	use crate::model::UserSession;
+
+	fn session(token: &str) -> UserSession {
+		serde_json::from_value(serde_json::json!({
+			"userId": "U-user",
+			"token": token,
+			"created": "2023-04-01T12:00:00Z",
+			"expire": "2023-04-02T12:00:00Z",
+			"rememberMe": false,
+			"sourceIP": "127.0.0.1",
+			"timestamp": "2023-04-01T12:00:00Z",
+			"secretMachineId": null,
+		}))
+		.expect("user session to deserialize")
+	}
+
+	#[test]
+	fn revoke_all_others() {
+		let auth = Authentication {
+			token: "current".to_owned(),
+			user_id: crate::id::User::try_from("U-user").unwrap(),
+		};
+		let sessions = [session("current"), session("other/1"), session("")];
+		let revokes = RevokeUserSession::all_others(&sessions, &auth);
+		assert_eq!(revokes.len(), 2);
+		let revoke = revokes[0].as_ref().unwrap();
+		assert_eq!(
+			revoke.url(&auth),
+			format!("{}/userSessions/U-user/other%2F1", crate::API_BASE_URI)
+		);
+		assert!(revoke.body(&auth).is_none());
+		assert!(!format!("{revoke:?}").contains("other"));
+		assert_eq!(revokes[1], Err(&sessions[2]));
+		assert!(RevokeUserSession::new(&sessions[2]).is_err());
+		assert!(sessions[0].is_expired());
+	}
+}
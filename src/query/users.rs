@@ -51,6 +51,7 @@ impl From<crate::id::User> for UserIdOrUsername {
 /// # Example usage
 ///
 /// ```no_run
+/// # #[cfg(feature = "http_client")]
 /// # tokio_test::block_on(async {
 /// # use neos::{api_client::{UnauthenticatedNeos, ApiClient}, query::UserSearch};
 /// # let USER_AGENT = String::new();
@@ -95,6 +96,7 @@ impl Queryable<NoAuthentication, crate::model::User> for UserInfo {
 /// # Example usage
 ///
 /// ```no_run
+/// # #[cfg(feature = "http_client")]
 /// # tokio_test::block_on(async {
 /// # use neos::{api_client::{UnauthenticatedNeos, ApiClient}, query::UserStatus};
 /// # let USER_AGENT = String::new();
@@ -123,7 +125,7 @@ impl UserStatus {
 
 impl Queryable<NoAuthentication, crate::model::UserStatus> for UserStatus {
 	fn url(&self, _: &NoAuthentication) -> String {
-		format!("{}/users/{}/status", crate::API_BASE_URI, self.user_id.as_ref(),)
+		format!("{}/users/{}/status", crate::API_BASE_URI, self.user_id.as_ref())
 	}
 }
 
@@ -0,0 +1,664 @@
+//! Parsing of Neos' rich text markup.
+//!
+//! Session names and descriptions for example can contain tags such as
+//! `<color=#ff0000>`, `<b>` and `<size=120%>`, similar to Unity's rich text.
+//! Like in Neos, only the tags that `TextMeshPro` supports are markup. Anything
+//! else, such as a literal `<3` or `<Cool Session>`, is kept as text.
+//!
+//! The parsed text can be rendered as sanitized HTML, ANSI colored terminal
+//! output or Discord flavored Markdown. Tags that can't be represented in the
+//! output format are dropped, keeping the text inside them.
+//!
+//! # Example usage
+//!
+//! ```
+//! use neos::rich_text::{RichText, Span, Style};
+//!
+//! let text = RichText::parse("<color=red>Hello</color> <3");
+//! assert_eq!(text.plain_text(), "Hello <3");
+//! assert_eq!(
+//! 	text.spans()[0],
+//! 	Span::Styled {
+//! 		style: Style::Color("red".to_owned()),
+//! 		children: vec![Span::Text("Hello".to_owned())],
+//! 	}
+//! );
+//! ```
+
+/// A style that's applied by a rich text tag
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Style {
+	/// `<color=...>`, with a name such as `red` or a hex value such as
+	/// `#ff0000`
+	Color(String),
+	/// `<b>`
+	Bold,
+	/// `<i>`
+	Italic,
+	/// `<u>`
+	Underline,
+	/// `<s>`
+	Strikethrough,
+	/// `<size=...>`, with a value such as `120%` or `12`
+	Size(String),
+	/// Any other supported tag, such as `<align=center>`
+	Other {
+		/// The lowercase name of the tag
+		name: String,
+		/// The value or attributes of the tag, if any
+		value: Option<String>,
+	},
+}
+
+impl Style {
+	fn new(name: String, value: Option<String>) -> Self {
+		match (name.as_str(), value) {
+			("color", Some(value)) => Self::Color(value),
+			("b", None) => Self::Bold,
+			("i", None) => Self::Italic,
+			("u", None) => Self::Underline,
+			("s", None) => Self::Strikethrough,
+			("size", Some(value)) => Self::Size(value),
+			(_, value) => Self::Other { name, value },
+		}
+	}
+
+	#[must_use]
+	/// The lowercase name of the tag, such as `color` or `b`
+	pub fn name(&self) -> &str {
+		match self {
+			Self::Color(_) => "color",
+			Self::Bold => "b",
+			Self::Italic => "i",
+			Self::Underline => "u",
+			Self::Strikethrough => "s",
+			Self::Size(_) => "size",
+			Self::Other { name, .. } => name,
+		}
+	}
+}
+
+/// A part of rich text
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Span {
+	/// Plain text
+	Text(String),
+	/// `<br>`
+	LineBreak,
+	/// `<sprite=...>` or `<sprite name="...">`, with the value or attributes
+	Sprite(String),
+	/// Text with a style applied to it
+	Styled {
+		/// The style of the tag
+		style: Style,
+		/// What the style applies to
+		children: Vec<Self>,
+	},
+}
+
+/// Rich text parsed into a tree of spans
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RichText(Vec<Span>);
+
+/// Names of the tags that `TextMeshPro` supports, anything else isn't markup
+const KNOWN_TAGS: &[&str] = &[
+	"align",
+	"allcaps",
+	"alpha",
+	"b",
+	"br",
+	"color",
+	"cspace",
+	"font",
+	"font-weight",
+	"gradient",
+	"i",
+	"indent",
+	"line-height",
+	"line-indent",
+	"link",
+	"lowercase",
+	"margin",
+	"margin-left",
+	"margin-right",
+	"mark",
+	"mspace",
+	"nobr",
+	"noparse",
+	"page",
+	"pos",
+	"rotate",
+	"s",
+	"size",
+	"smallcaps",
+	"space",
+	"sprite",
+	"strikethrough",
+	"style",
+	"sub",
+	"sup",
+	"u",
+	"underline",
+	"uppercase",
+	"voffset",
+	"width",
+];
+
+/// A tag before it's been turned into a span
+struct Tag {
+	is_closing: bool,
+	name: String,
+	value: Option<String>,
+}
+
+/// Tries to parse a tag from the start of the string.
+///
+/// Returns the tag and its length in bytes, or nothing if it isn't a
+/// [known](KNOWN_TAGS) tag.
+fn parse_tag(str: &str) -> Option<(Tag, usize)> {
+	let end = str.find('>')?;
+	let inner = &str[1..end];
+	if inner.contains('<') {
+		return None;
+	}
+
+	let (is_closing, inner) =
+		inner.strip_prefix('/').map_or((false, inner), |inner| (true, inner));
+
+	let name_len = inner
+		.find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+		.unwrap_or(inner.len());
+	let (name, rest) = inner.split_at(name_len);
+	let name = name.to_ascii_lowercase();
+	if !KNOWN_TAGS.contains(&name.as_str()) {
+		return None;
+	}
+
+	let value = if rest.is_empty() {
+		None
+	} else if let Some(value) = rest.strip_prefix('=') {
+		let value = value.trim();
+		let value = value
+			.strip_prefix('"')
+			.and_then(|value| value.strip_suffix('"'))
+			.unwrap_or(value);
+		Some(value.to_owned())
+	} else if rest.starts_with(char::is_whitespace) && !is_closing {
+		Some(rest.trim().to_owned())
+	} else {
+		return None;
+	};
+
+	Some((Tag { is_closing, name, value }, end + 1))
+}
+
+/// Adds text to the spans, merging it with previous text if possible
+fn push_text(spans: &mut Vec<Span>, text: &str) {
+	if text.is_empty() {
+		return;
+	}
+	if let Some(Span::Text(previous)) = spans.last_mut() {
+		previous.push_str(text);
+	} else {
+		spans.push(Span::Text(text.to_owned()));
+	}
+}
+
+impl RichText {
+	#[must_use]
+	/// Parses the rich text markup.
+	///
+	/// Never fails, malformed tags are treated as text and unclosed tags are
+	/// closed at the end.
+	pub fn parse(str: &str) -> Self {
+		let mut root = Vec::new();
+		let mut open: Vec<(Style, Vec<Span>)> = Vec::new();
+		let mut rest = str;
+
+		while let Some(start) = rest.find('<') {
+			let current = open.last_mut().map_or(&mut root, |(_, spans)| spans);
+			push_text(current, &rest[..start]);
+			rest = &rest[start..];
+
+			let Some((tag, len)) = parse_tag(rest) else {
+				push_text(current, "<");
+				rest = &rest[1..];
+				continue;
+			};
+			rest = &rest[len..];
+
+			if tag.is_closing {
+				if let Some(index) =
+					open.iter().rposition(|(style, _)| style.name() == tag.name)
+				{
+					while open.len() > index {
+						close(&mut root, &mut open);
+					}
+				}
+				continue;
+			}
+
+			match tag.name.as_str() {
+				"br" => current.push(Span::LineBreak),
+				"sprite" => current.push(Span::Sprite(tag.value.unwrap_or_default())),
+				"noparse" => {
+					let end = rest.to_ascii_lowercase().find("</noparse>");
+					let (text, after) =
+						end.map_or((rest, ""), |end| (&rest[..end], &rest[end + 10..]));
+					push_text(current, text);
+					rest = after;
+				}
+				_ => open.push((Style::new(tag.name, tag.value), Vec::new())),
+			}
+		}
+
+		let current = open.last_mut().map_or(&mut root, |(_, spans)| spans);
+		push_text(current, rest);
+		while !open.is_empty() {
+			close(&mut root, &mut open);
+		}
+
+		Self(root)
+	}
+
+	#[must_use]
+	/// The top level spans of the text
+	pub fn spans(&self) -> &[Span] { &self.0 }
+
+	#[must_use]
+	/// The text without any of the markup.
+	///
+	/// Line breaks are turned into newlines and sprites are left out.
+	pub fn plain_text(&self) -> String {
+		fn push(spans: &[Span], text: &mut String) {
+			for span in spans {
+				match span {
+					Span::Text(str) => text.push_str(str),
+					Span::LineBreak => text.push('\n'),
+					Span::Sprite(_) => {}
+					Span::Styled { children, .. } => push(children, text),
+				}
+			}
+		}
+
+		let mut text = String::new();
+		push(&self.0, &mut text);
+		text
+	}
+}
+
+impl RichText {
+	#[must_use]
+	/// Renders the text as HTML, with everything that isn't from a known tag
+	/// escaped.
+	///
+	/// Colors and sizes are only kept if they're valid, and sprites are dropped.
+	///
+	/// # Example usage
+	///
+	/// ```
+	/// # use neos::rich_text::RichText;
+	/// let text = RichText::parse("<b><color=red>1 < 2</color></b><script>");
+	/// assert_eq!(
+	/// 	text.to_html(),
+	/// 	"<b><span style=\"color:#ff0000\">1 &lt; 2</span></b>&lt;script&gt;"
+	/// );
+	/// ```
+	pub fn to_html(&self) -> String {
+		fn push(spans: &[Span], html: &mut String) {
+			for span in spans {
+				match span {
+					Span::Text(str) => push_html_escaped(str, html),
+					Span::LineBreak => html.push_str("<br>"),
+					Span::Sprite(_) => {}
+					Span::Styled { style, children } => {
+						let (open, close) = match style {
+							Style::Color(color) => match parse_color(color) {
+								Some((r, g, b)) => (
+									format!("<span style=\"color:#{r:02x}{g:02x}{b:02x}\">"),
+									"</span>",
+								),
+								None => (String::new(), ""),
+							},
+							Style::Bold => ("<b>".to_owned(), "</b>"),
+							Style::Italic => ("<i>".to_owned(), "</i>"),
+							Style::Underline => ("<u>".to_owned(), "</u>"),
+							Style::Strikethrough => ("<s>".to_owned(), "</s>"),
+							Style::Size(size) => match parse_size(size) {
+								Some((size, unit)) => (
+									format!("<span style=\"font-size:{size}{unit}\">"),
+									"</span>",
+								),
+								None => (String::new(), ""),
+							},
+							Style::Other { .. } => (String::new(), ""),
+						};
+						html.push_str(&open);
+						push(children, html);
+						html.push_str(close);
+					}
+				}
+			}
+		}
+
+		let mut html = String::new();
+		push(&self.0, &mut html);
+		html
+	}
+
+	#[must_use]
+	/// Renders the text with ANSI escape codes for terminals.
+	///
+	/// Colors are rendered as 24-bit colors, and control characters in the text
+	/// are removed so that the text itself can't contain escape codes.
+	///
+	/// # Example usage
+	///
+	/// ```
+	/// # use neos::rich_text::RichText;
+	/// let text = RichText::parse("<b>Hi</b>!");
+	/// assert_eq!(text.to_ansi(), "\x1b[1mHi\x1b[0m!");
+	/// ```
+	pub fn to_ansi(&self) -> String {
+		fn push(spans: &[Span], ansi: &mut String, active: &mut Vec<String>) {
+			for span in spans {
+				match span {
+					Span::Text(str) => {
+						ansi.extend(str.chars().filter(|c| *c == '\n' || !c.is_control()));
+					}
+					Span::LineBreak => ansi.push('\n'),
+					Span::Sprite(_) => {}
+					Span::Styled { style, children } => {
+						let code = match style {
+							Style::Color(color) => parse_color(color)
+								.map(|(r, g, b)| format!("\x1b[38;2;{r};{g};{b}m")),
+							Style::Bold => Some("\x1b[1m".to_owned()),
+							Style::Italic => Some("\x1b[3m".to_owned()),
+							Style::Underline => Some("\x1b[4m".to_owned()),
+							Style::Strikethrough => Some("\x1b[9m".to_owned()),
+							Style::Size(_) | Style::Other { .. } => None,
+						};
+						let Some(code) = code else {
+							push(children, ansi, active);
+							continue;
+						};
+						ansi.push_str(&code);
+						active.push(code);
+						push(children, ansi, active);
+						active.pop();
+						// Resetting is the only way to undo some of the styles
+						ansi.push_str("\x1b[0m");
+						for code in active.iter() {
+							ansi.push_str(code);
+						}
+					}
+				}
+			}
+		}
+
+		let mut ansi = String::new();
+		push(&self.0, &mut ansi, &mut Vec::new());
+		ansi
+	}
+
+	#[must_use]
+	/// Renders the text as Discord flavored Markdown.
+	///
+	/// Markdown syntax in the text is escaped, including `@` to prevent
+	/// mentions. Colors and sizes can't be represented so they're dropped.
+	///
+	/// # Example usage
+	///
+	/// ```
+	/// # use neos::rich_text::RichText;
+	/// let text = RichText::parse("<b>@everyone</b> <i>*hi*</i>");
+	/// assert_eq!(text.to_markdown(), "**\\@everyone** *\\*hi\\**");
+	/// ```
+	pub fn to_markdown(&self) -> String {
+		fn push(spans: &[Span], markdown: &mut String) {
+			for span in spans {
+				match span {
+					Span::Text(str) => {
+						for c in str.chars() {
+							if MARKDOWN_SPECIAL_CHARS.contains(&c) {
+								markdown.push('\\');
+							}
+							markdown.push(c);
+						}
+					}
+					Span::LineBreak => markdown.push('\n'),
+					Span::Sprite(_) => {}
+					Span::Styled { style, children } => {
+						let marker = match style {
+							Style::Bold => "**",
+							Style::Italic => "*",
+							Style::Underline => "__",
+							Style::Strikethrough => "~~",
+							Style::Color(_) | Style::Size(_) | Style::Other { .. } => "",
+						};
+						let mut inner = String::new();
+						push(children, &mut inner);
+						// Discord doesn't render markers around nothing
+						if inner.trim().is_empty() {
+							markdown.push_str(&inner);
+						} else {
+							markdown.push_str(marker);
+							markdown.push_str(&inner);
+							markdown.push_str(marker);
+						}
+					}
+				}
+			}
+		}
+
+		let mut markdown = String::new();
+		push(&self.0, &mut markdown);
+		markdown
+	}
+}
+
+/// Characters that have a special meaning in Discord's Markdown
+const MARKDOWN_SPECIAL_CHARS: &[char] = &[
+	'\\', '*', '_', '~', '`', '|', '>', '#', '-', '[', ']', '(', ')', '<', '@',
+	':',
+];
+
+/// Color names that Neos supports, with their (R,G,B) values
+const NAMED_COLORS: &[(&str, (u8, u8, u8))] = &[
+	("black", (0, 0, 0)),
+	("blue", (0, 0, 255)),
+	("cyan", (0, 255, 255)),
+	("gray", (128, 128, 128)),
+	("green", (0, 255, 0)),
+	("grey", (128, 128, 128)),
+	("magenta", (255, 0, 255)),
+	("orange", (255, 165, 0)),
+	("purple", (160, 32, 240)),
+	("red", (255, 0, 0)),
+	("white", (255, 255, 255)),
+	("yellow", (255, 255, 0)),
+];
+
+/// Parses a color name or a `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa` value
+/// into (R,G,B), ignoring any alpha.
+fn parse_color(color: &str) -> Option<(u8, u8, u8)> {
+	let color = color.trim().to_ascii_lowercase();
+	let Some(hex) = color.strip_prefix('#') else {
+		return NAMED_COLORS
+			.iter()
+			.find(|(name, _)| *name == color)
+			.map(|(_, rgb)| *rgb);
+	};
+	if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+		return None;
+	}
+
+	let channel = |index: usize, len: usize| {
+		let value =
+			u8::from_str_radix(hex.get(index * len..(index + 1) * len)?, 16).ok()?;
+		Some(if len == 1 { value * 17 } else { value })
+	};
+	let len = match hex.len() {
+		3 | 4 => 1,
+		6 | 8 => 2,
+		_ => return None,
+	};
+	Some((channel(0, len)?, channel(1, len)?, channel(2, len)?))
+}
+
+/// Parses a size such as `120%` or `1.5em` into the number and CSS unit.
+///
+/// Sizes without a unit are in pixels.
+fn parse_size(size: &str) -> Option<(f32, &'static str)> {
+	let size = size.trim();
+	let (number, unit) = [("%", "%"), ("px", "px"), ("em", "em")]
+		.into_iter()
+		.find_map(|(suffix, unit)| size.strip_suffix(suffix).map(|n| (n, unit)))
+		.unwrap_or((size, "px"));
+	let number: f32 = number.parse().ok()?;
+	(number.is_finite() && number >= 0.0).then_some((number, unit))
+}
+
+/// Pushes the text to the HTML with special characters escaped
+fn push_html_escaped(text: &str, html: &mut String) {
+	for c in text.chars() {
+		match c {
+			'&' => html.push_str("&amp;"),
+			'<' => html.push_str("&lt;"),
+			'>' => html.push_str("&gt;"),
+			'"' => html.push_str("&quot;"),
+			'\'' => html.push_str("&#39;"),
+			c => html.push(c),
+		}
+	}
+}
+
+/// Closes the innermost open tag, adding it to its parent
+fn close(root: &mut Vec<Span>, open: &mut Vec<(Style, Vec<Span>)>) {
+	if let Some((style, children)) = open.pop() {
+		let parent = open.last_mut().map_or(root, |(_, spans)| spans);
+		parent.push(Span::Styled { style, children });
+	}
+}
+
+impl From<&str> for RichText {
+	fn from(str: &str) -> Self { Self::parse(str) }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{RichText, Span, Style};
+
+	fn text(str: &str) -> Span { Span::Text(str.to_owned()) }
+
+	#[test]
+	fn literal_angle_brackets() {
+		let parsed = RichText::parse("a < b > c <3 <> <=5>");
+		assert_eq!(parsed.spans(), &[text("a < b > c <3 <> <=5>")]);
+	}
+
+	#[test]
+	fn nesting() {
+		let parsed =
+			RichText::parse("<b>bold <COLOR=\"#ff0000\">red</color></b><br>done");
+		assert_eq!(
+			parsed.spans(),
+			&[
+				Span::Styled {
+					style: Style::Bold,
+					children: vec![
+						text("bold "),
+						Span::Styled {
+							style: Style::Color("#ff0000".to_owned()),
+							children: vec![text("red")],
+						},
+					],
+				},
+				Span::LineBreak,
+				text("done"),
+			]
+		);
+		assert_eq!(parsed.plain_text(), "bold red\ndone");
+	}
+
+	#[test]
+	fn mismatched_tags() {
+		let parsed = RichText::parse("</i><b><i>x</b>y<size=120%>z");
+		assert_eq!(
+			parsed.spans(),
+			&[
+				Span::Styled {
+					style: Style::Bold,
+					children: vec![Span::Styled {
+						style: Style::Italic,
+						children: vec![text("x")],
+					}],
+				},
+				text("y"),
+				Span::Styled {
+					style: Style::Size("120%".to_owned()),
+					children: vec![text("z")],
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn html() {
+		let parsed = RichText::parse(
+			"<size=12><color=#F00a>\"a\"</color></size><color=javascript:x>&</color>\
+			 <sprite=1><align=center>x</align><br>",
+		);
+		assert_eq!(
+			parsed.to_html(),
+			"<span style=\"font-size:12px\"><span \
+			 style=\"color:#ff0000\">&quot;a&quot;</span></span>&amp;x<br>"
+		);
+	}
+
+	#[test]
+	fn ansi() {
+		let parsed = RichText::parse("<color=#00ff00>a<i>b\x1b[2J</i>c</color>");
+		assert_eq!(
+			parsed.to_ansi(),
+			"\x1b[38;2;0;255;0ma\x1b[3mb[2J\x1b[0m\x1b[38;2;0;255;0mc\x1b[0m"
+		);
+	}
+
+	#[test]
+	fn markdown() {
+		let parsed = RichText::parse("<u>a_b</u><s> </s><color=red>c</color><br>");
+		assert_eq!(parsed.to_markdown(), "__a\\_b__ c\n");
+	}
+
+	#[test]
+	fn sprites_and_noparse() {
+		let parsed =
+			RichText::parse("<sprite name=\"heart\"><noparse><b></noparse>");
+		assert_eq!(
+			parsed.spans(),
+			&[Span::Sprite("name=\"heart\"".to_owned()), text("<b>")]
+		);
+		assert_eq!(parsed.plain_text(), "<b>");
+	}
+
+	#[test]
+	fn unknown_tags() {
+		let parsed = RichText::parse("<Cool Session> <b>x</bold></cool>");
+		assert_eq!(
+			parsed.spans(),
+			&[
+				text("<Cool Session> "),
+				Span::Styled {
+					style: Style::Bold,
+					children: vec![text("x</bold></cool>")],
+				},
+			]
+		);
+		assert_eq!(parsed.plain_text(), "<Cool Session> x</bold></cool>");
+		assert_eq!(
+			RichText::parse("<Cool Session>").to_html(),
+			"&lt;Cool Session&gt;"
+		);
+	}
+}
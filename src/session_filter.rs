@@ -0,0 +1,369 @@
+//! Client side filtering and sorting of sessions.
+//!
+//! Useful with the results of [`Sessions`](crate::query::Sessions), which
+//! lists every public session.
+//!
+//! # Example usage
+//!
+//! ```
+//! use neos::{
+//! 	model::SessionAccessLevel,
+//! 	session_filter::{SessionFilter, SessionSort},
+//! };
+//!
+//! // Normally you'd get the sessions by querying the API
+//! let sessions: Vec<neos::model::SessionInfo> = Vec::new();
+//! let filter = SessionFilter::default()
+//! 	.min_active_users(1)
+//! 	.min_access_level(SessionAccessLevel::RegisteredUsers)
+//! 	.exclude_tag("nsfw")
+//! 	.sorted_by(SessionSort::ActiveUsers, true);
+//! for session in filter.apply(&sessions) {
+//! 	println!("{}", session.stripped_name());
+//! }
+//! ```
+
+use std::cmp::Ordering;
+
+use crate::model::{SessionAccessLevel, SessionInfo};
+
+/// What to sort sessions by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SessionSort {
+	/// The amount of users that are focused on the session
+	ActiveUsers,
+	/// The amount of users that have joined the session
+	JoinedUsers,
+	/// How long the session has been running for
+	Uptime,
+	/// When the session was last updated
+	LastUpdate,
+}
+
+impl SessionSort {
+	#[must_use]
+	/// Compares the sessions in ascending order, such as the least active users
+	/// or the shortest uptime first
+	pub fn compare(&self, a: &SessionInfo, b: &SessionInfo) -> Ordering {
+		match self {
+			Self::ActiveUsers => a.active_users.cmp(&b.active_users),
+			Self::JoinedUsers => a.joined_users.cmp(&b.joined_users),
+			Self::Uptime => b.session_begin_time.cmp(&a.session_begin_time),
+			Self::LastUpdate => a.last_update_time.cmp(&b.last_update_time),
+		}
+	}
+}
+
+/// Predicates for filtering sessions, with an optional sort order.
+///
+/// By default only hides ended and invalid sessions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct SessionFilter {
+	/// The least amount of active users that the session needs to have
+	pub min_active_users: Option<u8>,
+	/// The least open access level that the session needs to have
+	pub min_access_level: Option<SessionAccessLevel>,
+	/// The most open access level that the session can have.
+	///
+	/// Unknown access levels are ordered as more open than the known ones, see
+	/// [`SessionAccessLevel`], so this hides them.
+	pub max_access_level: Option<SessionAccessLevel>,
+	/// If only sessions hosted by headless instances are included
+	pub headless_only: bool,
+	/// If only sessions that are suitable for mobile clients are included
+	pub mobile_friendly_only: bool,
+	/// The version of Neos that the sessions need to be hosting
+	pub neos_version: Option<String>,
+	/// The compatibility hash that the sessions need to have
+	pub compatibility_hash: Option<String>,
+	/// Tags that the session needs to have all of, case insensitively
+	pub include_tags: Vec<String>,
+	/// Tags that the session can't have any of, case insensitively
+	pub exclude_tags: Vec<String>,
+	/// If sessions that have ended are hidden
+	pub hide_ended: bool,
+	/// If sessions that aren't valid are hidden
+	pub hide_invalid: bool,
+	/// What to sort the sessions by
+	pub sort: Option<SessionSort>,
+	/// If the sort order is descending instead of ascending
+	pub sort_descending: bool,
+}
+
+impl Default for SessionFilter {
+	fn default() -> Self {
+		Self {
+			min_active_users: None,
+			min_access_level: None,
+			max_access_level: None,
+			headless_only: false,
+			mobile_friendly_only: false,
+			neos_version: None,
+			compatibility_hash: None,
+			include_tags: Vec::new(),
+			exclude_tags: Vec::new(),
+			hide_ended: true,
+			hide_invalid: true,
+			sort: None,
+			sort_descending: false,
+		}
+	}
+}
+
+impl SessionFilter {
+	#[must_use]
+	/// Sets the least amount of active users
+	pub const fn min_active_users(mut self, min_active_users: u8) -> Self {
+		self.min_active_users = Some(min_active_users);
+		self
+	}
+
+	#[must_use]
+	/// Sets the least open access level
+	pub fn min_access_level(mut self, level: SessionAccessLevel) -> Self {
+		self.min_access_level = Some(level);
+		self
+	}
+
+	#[must_use]
+	/// Sets the most open access level, which also hides sessions with unknown
+	/// access levels
+	pub fn max_access_level(mut self, level: SessionAccessLevel) -> Self {
+		self.max_access_level = Some(level);
+		self
+	}
+
+	#[must_use]
+	/// Sets the headless only field's value
+	pub const fn headless_only(mut self, headless_only: bool) -> Self {
+		self.headless_only = headless_only;
+		self
+	}
+
+	#[must_use]
+	/// Sets the mobile friendly only field's value
+	pub const fn mobile_friendly_only(
+		mut self, mobile_friendly_only: bool,
+	) -> Self {
+		self.mobile_friendly_only = mobile_friendly_only;
+		self
+	}
+
+	#[must_use]
+	/// Only includes sessions that are compatible with the version and hash,
+	/// such as the ones from an user's status
+	pub fn compatible_with(
+		mut self, neos_version: impl Into<Option<String>>,
+		compatibility_hash: impl Into<Option<String>>,
+	) -> Self {
+		self.neos_version = neos_version.into();
+		self.compatibility_hash = compatibility_hash.into();
+		self
+	}
+
+	#[must_use]
+	/// Adds a tag that the sessions need to have
+	pub fn include_tag(mut self, tag: impl Into<String>) -> Self {
+		self.include_tags.push(tag.into());
+		self
+	}
+
+	#[must_use]
+	/// Adds a tag that the sessions can't have
+	pub fn exclude_tag(mut self, tag: impl Into<String>) -> Self {
+		self.exclude_tags.push(tag.into());
+		self
+	}
+
+	#[must_use]
+	/// Sets the hide ended field's value
+	pub const fn hide_ended(mut self, hide_ended: bool) -> Self {
+		self.hide_ended = hide_ended;
+		self
+	}
+
+	#[must_use]
+	/// Sets the hide invalid field's value
+	pub const fn hide_invalid(mut self, hide_invalid: bool) -> Self {
+		self.hide_invalid = hide_invalid;
+		self
+	}
+
+	#[must_use]
+	/// Sets what the sessions are sorted by
+	pub const fn sorted_by(
+		mut self, sort: SessionSort, descending: bool,
+	) -> Self {
+		self.sort = Some(sort);
+		self.sort_descending = descending;
+		self
+	}
+
+	#[must_use]
+	/// If the session passes all of the predicates
+	pub fn matches(&self, session: &SessionInfo) -> bool {
+		let has_tag = |tag: &String| {
+			session.tags.iter().any(|other| other.eq_ignore_ascii_case(tag))
+		};
+
+		!(self.hide_ended && session.has_ended
+			|| self.hide_invalid && !session.is_valid
+			|| self.headless_only && !session.is_headless_host
+			|| self.mobile_friendly_only && !session.is_mobile_friendly)
+			&& self.min_active_users.is_none_or(|min| session.active_users >= min)
+			&& self
+				.min_access_level
+				.as_ref()
+				.is_none_or(|min| &session.access_level >= min)
+			&& self
+				.max_access_level
+				.as_ref()
+				.is_none_or(|max| &session.access_level <= max)
+			&& self
+				.neos_version
+				.as_ref()
+				.is_none_or(|version| &session.neos_version == version)
+			&& self
+				.compatibility_hash
+				.as_ref()
+				.is_none_or(|hash| &session.compatibility_hash == hash)
+			&& self.include_tags.iter().all(has_tag)
+			&& !self.exclude_tags.iter().any(has_tag)
+	}
+
+	#[must_use]
+	/// Filters and sorts the sessions
+	pub fn apply<'a>(
+		&self, sessions: impl IntoIterator<Item = &'a SessionInfo>,
+	) -> Vec<&'a SessionInfo> {
+		let mut sessions: Vec<&SessionInfo> =
+			sessions.into_iter().filter(|session| self.matches(session)).collect();
+
+		if let Some(sort) = &self.sort {
+			sessions.sort_by(|a, b| {
+				let ordering = sort.compare(a, b);
+				if self.sort_descending {
+					ordering.reverse()
+				} else {
+					ordering
+				}
+			});
+		}
+
+		sessions
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{SessionFilter, SessionSort};
+	use crate::model::{SessionAccessLevel, SessionInfo};
+
+	fn session(
+		id: &str, active_users: u8, access_level: &str, tags: &[&str],
+	) -> SessionInfo {
+		let mut json = crate::model::fixtures::session_info();
+		json["name"] = id.into();
+		json["tags"] = tags.into();
+		json["sessionId"] = id.into();
+		json["normalizedSessionId"] = id.to_lowercase().into();
+		for count in
+			["joinedUsers", "activeUsers", "totalJoinedUsers", "totalActiveUsers"]
+		{
+			json[count] = active_users.into();
+		}
+		json["accessLevel"] = access_level.into();
+		serde_json::from_value(json).expect("session to deserialize")
+	}
+
+	#[test]
+	fn filters_and_sorts() {
+		let sessions = [
+			session("S-a", 2, "Anyone", &["game"]),
+			session("S-b", 0, "Anyone", &[]),
+			session("S-c", 5, "RegisteredUsers", &["Game", "nsfw"]),
+			session("S-d", 8, "Friends", &["game"]),
+			session("S-e", 3, "Anyone", &["game"]),
+		];
+		let filter = SessionFilter::default()
+			.min_active_users(1)
+			.min_access_level(SessionAccessLevel::RegisteredUsers)
+			.include_tag("GAME")
+			.sorted_by(SessionSort::ActiveUsers, true);
+		let ids: Vec<&str> = filter
+			.apply(&sessions)
+			.iter()
+			.map(|session| session.id.as_ref())
+			.collect();
+		assert_eq!(ids, ["S-c", "S-e", "S-a"]);
+
+		let filter = filter.exclude_tag("NSFW");
+		assert_eq!(filter.apply(&sessions).len(), 2);
+	}
+
+	#[test]
+	fn max_access_level() {
+		let sessions = [
+			session("S-a", 1, "Private", &[]),
+			session("S-b", 1, "Friends", &[]),
+			session("S-c", 1, "RegisteredUsers", &[]),
+			session("S-d", 1, "Anyone", &[]),
+		];
+		let filter = SessionFilter::default()
+			.min_access_level(SessionAccessLevel::Friends)
+			.max_access_level(SessionAccessLevel::RegisteredUsers);
+		let ids: Vec<&str> = filter
+			.apply(&sessions)
+			.iter()
+			.map(|session| session.id.as_ref())
+			.collect();
+		assert_eq!(ids, ["S-b", "S-c"]);
+
+		let unknown = session("S-e", 1, "Contacts", &[]);
+		let filter = SessionFilter::default();
+		assert!(filter.matches(&unknown));
+		assert!(!filter
+			.max_access_level(SessionAccessLevel::Anyone)
+			.matches(&unknown));
+	}
+
+	#[test]
+	fn time_sorts() {
+		let at = |id: &str, begin: i64, update: i64| {
+			let mut session = session(id, 1, "Anyone", &[]);
+			session.session_begin_time =
+				time::OffsetDateTime::from_unix_timestamp(begin).unwrap();
+			session.last_update_time =
+				time::OffsetDateTime::from_unix_timestamp(update).unwrap();
+			session
+		};
+		let sessions =
+			[at("S-a", 100, 300), at("S-b", 200, 100), at("S-c", 0, 200)];
+		let sorted = |sort: SessionSort, descending: bool| -> Vec<&str> {
+			SessionFilter::default()
+				.sorted_by(sort, descending)
+				.apply(&sessions)
+				.iter()
+				.map(|session| session.id.as_ref())
+				.collect()
+		};
+
+		// The session that began the latest has the shortest uptime
+		assert_eq!(sorted(SessionSort::Uptime, false), ["S-b", "S-a", "S-c"]);
+		assert_eq!(sorted(SessionSort::Uptime, true), ["S-c", "S-a", "S-b"]);
+		assert_eq!(sorted(SessionSort::LastUpdate, false), ["S-b", "S-c", "S-a"]);
+		assert_eq!(sorted(SessionSort::LastUpdate, true), ["S-a", "S-c", "S-b"]);
+	}
+
+	#[test]
+	fn hides_ended() {
+		let mut ended = session("S-a", 1, "Anyone", &[]);
+		ended.has_ended = true;
+		assert!(!SessionFilter::default().matches(&ended));
+		assert!(SessionFilter::default().hide_ended(false).matches(&ended));
+		assert!(!SessionFilter::default()
+			.mobile_friendly_only(true)
+			.matches(&ended));
+	}
+}
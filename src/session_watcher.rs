@@ -0,0 +1,356 @@
+//! Watching the lifecycle of publicly listed sessions by polling them.
+//!
+//! The [`SessionWatcher`] keeps the latest known details of each session, and
+//! turns new [`Sessions`](crate::query::Sessions) query results into
+//! [`SessionEvent`]s.
+//!
+//! # Example usage
+//!
+//! ```
+//! # use neos::session_watcher::{SessionEvent, SessionWatcher};
+//! let mut watcher = SessionWatcher::default();
+//! // Normally you'd get the sessions by querying the API with `watcher.query()`
+//! let sessions: Vec<neos::model::SessionInfo> = Vec::new();
+//! for event in watcher.update(sessions) {
+//! 	if let SessionEvent::Started(session) = event {
+//! 		println!("{} started", session.stripped_name());
+//! 	}
+//! }
+//! ```
+//!
+//! With the `http_client` feature the polling can also be done in a
+//! background task, see [`SessionWatcher::watch`].
+
+use std::collections::{HashMap, HashSet};
+
+use time::OffsetDateTime;
+
+use crate::model::{SessionAccessLevel, SessionInfo, SessionUser};
+
+/// A change in the lifecycle of a session
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SessionEvent {
+	/// A session that wasn't known before was seen
+	Started(Box<SessionInfo>),
+	/// The session was marked as having ended
+	Ended {
+		/// The ID of the session
+		id: crate::id::Session,
+	},
+	/// The session isn't listed anymore, without having been seen ending.
+	///
+	/// Such as when it's made private, or shut down between polls.
+	Unlisted {
+		/// The ID of the session
+		id: crate::id::Session,
+	},
+	/// An user joined the session
+	UserJoined {
+		/// The ID of the session
+		id: crate::id::Session,
+		/// The user that joined
+		user: SessionUser,
+	},
+	/// An user left the session
+	UserLeft {
+		/// The ID of the session
+		id: crate::id::Session,
+		/// The user that left
+		user: SessionUser,
+	},
+	/// The access level of the session changed
+	AccessLevelChanged {
+		/// The ID of the session
+		id: crate::id::Session,
+		/// The previous access level
+		from: SessionAccessLevel,
+		/// The new access level
+		to: SessionAccessLevel,
+	},
+	/// The host of the session went away
+	HostAway {
+		/// The ID of the session
+		id: crate::id::Session,
+		/// When the host went away
+		since: OffsetDateTime,
+	},
+	/// The host of the session came back from being away
+	HostReturned {
+		/// The ID of the session
+		id: crate::id::Session,
+	},
+}
+
+impl SessionEvent {
+	#[must_use]
+	/// The ID of the session that the event is about
+	pub const fn id(&self) -> &crate::id::Session {
+		match self {
+			Self::Started(session) => &session.id,
+			Self::Ended { id }
+			| Self::Unlisted { id }
+			| Self::UserJoined { id, .. }
+			| Self::UserLeft { id, .. }
+			| Self::AccessLevelChanged { id, .. }
+			| Self::HostAway { id, .. }
+			| Self::HostReturned { id } => id,
+		}
+	}
+}
+
+/// Keeps track of the sessions' latest details
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionWatcher {
+	sessions: HashMap<crate::id::Session, SessionInfo>,
+	query: crate::query::Sessions,
+}
+
+impl SessionWatcher {
+	#[must_use]
+	/// Creates a new watcher that polls with the given query
+	pub fn new(query: crate::query::Sessions) -> Self {
+		Self { sessions: HashMap::new(), query }
+	}
+
+	#[must_use]
+	/// The latest known details of the sessions
+	pub const fn sessions(&self) -> &HashMap<crate::id::Session, SessionInfo> {
+		&self.sessions
+	}
+
+	#[must_use]
+	/// The latest known details of a session
+	pub fn session(&self, id: &crate::id::Session) -> Option<&SessionInfo> {
+		self.sessions.get(id)
+	}
+
+	#[must_use]
+	/// The query that the sessions are polled with
+	pub const fn query(&self) -> &crate::query::Sessions { &self.query }
+
+	/// Updates the tracked sessions with a full listing, returning what changed.
+	///
+	/// Sessions that were tracked but aren't in the listing are forgotten.
+	pub fn update(
+		&mut self, sessions: impl IntoIterator<Item = SessionInfo>,
+	) -> Vec<SessionEvent> {
+		let mut events = Vec::new();
+		let mut old_sessions = std::mem::take(&mut self.sessions);
+
+		for session in sessions {
+			if let Some(old) = old_sessions.remove(&session.id) {
+				diff(&old, &session, &mut events);
+			} else {
+				events.push(SessionEvent::Started(Box::new(session.clone())));
+				if session.has_ended {
+					events.push(SessionEvent::Ended { id: session.id.clone() });
+				}
+			}
+			self.sessions.insert(session.id.clone(), session);
+		}
+
+		events.extend(
+			old_sessions
+				.into_values()
+				.filter(|session| !session.has_ended)
+				.map(|session| SessionEvent::Unlisted { id: session.id }),
+		);
+
+		events
+	}
+
+	#[cfg(feature = "http_client")]
+	/// Queries the sessions and updates the tracked ones
+	///
+	/// # Errors
+	///
+	/// If querying the sessions fails
+	pub async fn poll(
+		&mut self, client: &crate::api_client::UnauthenticatedNeos,
+	) -> Result<Vec<SessionEvent>, crate::api_client::ApiError> {
+		use crate::api_client::ApiClient;
+
+		let sessions = client.query(self.query.clone()).await?;
+		Ok(self.update(sessions))
+	}
+
+	#[cfg(feature = "http_client")]
+	/// Polls the sessions in a background task, streaming the events.
+	///
+	/// The first poll happens immediately, and reports every listed session as
+	/// started. Failed polls are sent as errors without stopping the task, which
+	/// only stops once the stream is dropped.
+	///
+	/// # Panics
+	///
+	/// If called outside of a Tokio runtime
+	///
+	/// # Example usage
+	///
+	/// ```no_run
+	/// # use std::{sync::Arc, time::Duration};
+	/// # use neos::{api_client::UnauthenticatedNeos, session_watcher::SessionWatcher};
+	/// use tokio_stream::StreamExt;
+	///
+	/// # tokio_test::block_on(async {
+	/// # let USER_AGENT = String::new();
+	/// let client = Arc::new(UnauthenticatedNeos::new(USER_AGENT).unwrap());
+	/// let query = neos::query::Sessions::default().min_active_users(1);
+	/// let mut events =
+	/// 	SessionWatcher::new(query).watch(client, Duration::from_secs(30));
+	/// while let Some(event) = events.next().await {
+	/// 	println!("{event:?}");
+	/// }
+	/// # })
+	/// ```
+	pub fn watch(
+		mut self, client: std::sync::Arc<crate::api_client::UnauthenticatedNeos>,
+		interval: std::time::Duration,
+	) -> tokio_stream::wrappers::ReceiverStream<
+		Result<SessionEvent, crate::api_client::ApiError>,
+	> {
+		let (sender, receiver) = tokio::sync::mpsc::channel(64);
+
+		tokio::spawn(async move {
+			let mut interval = tokio::time::interval(interval);
+			interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+			while !sender.is_closed() {
+				interval.tick().await;
+				let results: Vec<_> = match self.poll(&client).await {
+					Ok(events) => events.into_iter().map(Ok).collect(),
+					Err(err) => vec![Err(err)],
+				};
+				for result in results {
+					if sender.send(result).await.is_err() {
+						return;
+					}
+				}
+			}
+		});
+
+		tokio_stream::wrappers::ReceiverStream::new(receiver)
+	}
+}
+
+/// Users don't always have an ID, so falls back to the username
+fn user_key(user: &SessionUser) -> &str {
+	user.id.as_ref().map_or(&user.username, AsRef::as_ref)
+}
+
+fn diff(old: &SessionInfo, new: &SessionInfo, events: &mut Vec<SessionEvent>) {
+	let id = || new.id.clone();
+
+	if old.access_level != new.access_level {
+		events.push(SessionEvent::AccessLevelChanged {
+			id: id(),
+			from: old.access_level.clone(),
+			to: new.access_level.clone(),
+		});
+	}
+
+	let old_users: HashSet<&str> = old.users.iter().map(user_key).collect();
+	let new_users: HashSet<&str> = new.users.iter().map(user_key).collect();
+	events.extend(
+		new
+			.users
+			.iter()
+			.filter(|user| !old_users.contains(user_key(user)))
+			.map(|user| SessionEvent::UserJoined { id: id(), user: user.clone() }),
+	);
+	events.extend(
+		old
+			.users
+			.iter()
+			.filter(|user| !new_users.contains(user_key(user)))
+			.map(|user| SessionEvent::UserLeft { id: id(), user: user.clone() }),
+	);
+
+	match (old.away_since, new.away_since) {
+		(None, Some(since)) => {
+			events.push(SessionEvent::HostAway { id: id(), since });
+		}
+		(Some(_), None) => events.push(SessionEvent::HostReturned { id: id() }),
+		_ => {}
+	}
+
+	if !old.has_ended && new.has_ended {
+		events.push(SessionEvent::Ended { id: id() });
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{SessionEvent, SessionWatcher};
+	use crate::model::{SessionAccessLevel, SessionInfo};
+
+	fn session(
+		access_level: &str, users: &[&str], away_since: Option<&str>,
+		has_ended: bool,
+	) -> SessionInfo {
+		let mut json = crate::model::fixtures::session_info();
+		json["sessionUsers"] = users
+			.iter()
+			.map(|username| crate::model::fixtures::session_user(username))
+			.collect();
+		for count in
+			["joinedUsers", "activeUsers", "totalJoinedUsers", "totalActiveUsers"]
+		{
+			json[count] = users.len().into();
+		}
+		json["awaySince"] = away_since.into();
+		json["accessLevel"] = access_level.into();
+		json["hasEnded"] = has_ended.into();
+		serde_json::from_value(json).expect("session to deserialize")
+	}
+
+	#[test]
+	fn started_and_unlisted() {
+		let mut watcher = SessionWatcher::default();
+		let events = watcher.update([session("Anyone", &[], None, false)]);
+		assert!(matches!(events.as_slice(), [SessionEvent::Started(_)]));
+		let events = watcher.update([]);
+		assert!(matches!(events.as_slice(), [SessionEvent::Unlisted { .. }]));
+		assert!(watcher.sessions().is_empty());
+	}
+
+	#[test]
+	fn lifecycle() {
+		let mut watcher = SessionWatcher::default();
+		watcher.update([session("Anyone", &["a", "b"], None, false)]);
+		let events = watcher.update([session(
+			"Friends",
+			&["b", "c"],
+			Some("2023-04-01T13:00:00Z"),
+			false,
+		)]);
+		assert_eq!(events.len(), 4);
+		assert!(matches!(
+			&events[0],
+			SessionEvent::AccessLevelChanged {
+				from: SessionAccessLevel::Anyone,
+				to: SessionAccessLevel::Friends,
+				..
+			}
+		));
+		assert!(
+			matches!(&events[1], SessionEvent::UserJoined { user, .. } if user.username == "c")
+		);
+		assert!(
+			matches!(&events[2], SessionEvent::UserLeft { user, .. } if user.username == "a")
+		);
+		assert!(matches!(&events[3], SessionEvent::HostAway { .. }));
+
+		let events = watcher.update([session("Friends", &[], None, true)]);
+		assert!(matches!(
+			events.as_slice(),
+			[
+				SessionEvent::UserLeft { .. },
+				SessionEvent::UserLeft { .. },
+				SessionEvent::HostReturned { .. },
+				SessionEvent::Ended { .. },
+			]
+		));
+		assert!(watcher.update([]).is_empty());
+	}
+}
@@ -1,13 +1,23 @@
 pub mod opt_rfc3339 {
 	//! Time serde for date time's RFC3339 where errors are converted to None.
 	#![allow(clippy::unnecessary_wraps)]
+	#![allow(clippy::ref_option)]
 	use serde::{Deserializer, Serializer};
 	use time::{serde::rfc3339, OffsetDateTime};
 
 	pub fn deserialize<'a, D: Deserializer<'a>>(
 		deserializer: D,
 	) -> Result<Option<OffsetDateTime>, D::Error> {
-		rfc3339::option::deserialize(deserializer).map_or_else(|_| Ok(None), Ok)
+		rfc3339::option::deserialize(deserializer).map_or_else(
+			|err| {
+				crate::diagnostics::report(
+					crate::diagnostics::FallbackKind::ErrorDefaulted,
+					err,
+				);
+				Ok(None)
+			},
+			Ok,
+		)
 	}
 
 	pub fn serialize<S: Serializer>(
@@ -17,32 +27,102 @@ pub mod opt_rfc3339 {
 	}
 }
 
-#[cfg(feature = "rand_util")]
 #[must_use]
-/// Generates a new (not cryptographically safe) pseudorandom string
+/// Percent-encodes the value so that it can be used as a single URL path
+/// segment
+pub fn encode_path_segment(value: &str) -> String {
+	// Spaces are encoded as `+` in form data, but `+` itself is encoded.
+	form_urlencoded::byte_serialize(value.as_bytes())
+		.collect::<String>()
+		.replace('+', "%20")
+}
+
+#[cfg(feature = "rand_util")]
+/// Fills the buffer with random bytes.
 ///
-/// The output string's char count is `bytes_count` multiplied by two
-pub fn random_ascii_string(bytes_count: u8) -> String {
-	// By using nanorand we avoid pulling in really heavy deps.
-	use nanorand::Rng;
+/// Uses the OS' cryptographically secure RNG with the `secure_rand` feature,
+/// and a faster (not cryptographically safe) pseudorandom one otherwise.
+///
+/// # Panics
+///
+/// If the OS' RNG is unavailable with the `secure_rand` feature
+pub fn fill_random(bytes: &mut [u8]) {
+	#[cfg(feature = "secure_rand")]
+	getrandom::getrandom(bytes).expect("the OS' RNG to be available");
 
-	const DICT: &[char; 16] = &[
-		'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o',
-		'p',
-	];
+	#[cfg(not(feature = "secure_rand"))]
+	{
+		// By using nanorand we avoid pulling in really heavy deps.
+		use nanorand::Rng;
+		nanorand::tls_rng().fill(bytes);
+	}
+}
 
-	let mut bits = [0u8].repeat(bytes_count as usize);
+#[cfg(feature = "rand_util")]
+#[must_use]
+/// Generates a new random version 4 UUID, see [`fill_random`] for the safety
+pub fn random_uuid() -> uuid::Uuid {
+	let mut bytes = [0u8; 16];
+	fill_random(&mut bytes);
+	uuid::Builder::from_random_bytes(bytes).into_uuid()
+}
 
-	nanorand::tls_rng().fill(&mut bits);
+#[cfg(feature = "rand_util")]
+/// The lowercase RFC 4648 base32 alphabet
+const BASE32_DICT: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
 
-	let mut string = String::with_capacity(bytes_count as usize * 2);
+#[cfg(feature = "rand_util")]
+#[must_use]
+/// Encodes the bytes as lowercase base32 without padding
+pub fn base32(bytes: &[u8]) -> String {
+	let mut string = String::with_capacity(bytes.len().div_ceil(5) * 8);
+	let mut buffer: u16 = 0;
+	let mut bits: u8 = 0;
 
-	for byte in bits {
-		let first_char_index = byte & 0xF;
-		let second_char_index = byte >> 4;
-		string.push(DICT[first_char_index as usize]);
-		string.push(DICT[second_char_index as usize]);
+	for byte in bytes {
+		buffer = (buffer << 8) | u16::from(*byte);
+		bits += 8;
+		while bits >= 5 {
+			bits -= 5;
+			string.push(BASE32_DICT[usize::from((buffer >> bits) & 0x1F)] as char);
+		}
+	}
+	if bits > 0 {
+		string
+			.push(BASE32_DICT[usize::from((buffer << (5 - bits)) & 0x1F)] as char);
 	}
 
 	string
 }
+
+#[cfg(feature = "rand_util")]
+#[must_use]
+/// Generates a new random secret machine ID, see [`fill_random`] for the
+/// safety.
+///
+/// Like the ones that Neos generates, 32 random bytes encoded as lowercase
+/// base32, so 52 characters.
+pub fn random_machine_id() -> String {
+	let mut bytes = [0u8; 32];
+	fill_random(&mut bytes);
+	base32(&bytes)
+}
+
+#[cfg(all(test, feature = "rand_util"))]
+mod tests {
+	#[test]
+	fn base32() {
+		assert_eq!(super::base32(b""), "");
+		assert_eq!(super::base32(b"f"), "my");
+		assert_eq!(super::base32(b"fooba"), "mzxw6ytb");
+		assert_eq!(super::base32(b"foobar"), "mzxw6ytboi");
+	}
+
+	#[test]
+	fn random_machine_id() {
+		let id = super::random_machine_id();
+		assert_eq!(id.len(), 52);
+		assert!(id.bytes().all(|c| super::BASE32_DICT.contains(&c)));
+		assert_ne!(id, super::random_machine_id());
+	}
+}
@@ -1,5 +1,6 @@
 #![cfg(feature = "http_client")]
 
+use neos::api_client::AuthenticatedNeos;
 use racal::reqwest::{ApiClient, ApiError};
 
 mod common;
@@ -7,8 +8,24 @@ mod common;
 #[tokio::test]
 #[ignore]
 async fn extend_session() -> Result<(), ApiError> {
-	let client = common::api_auth();
+	let recorder = common::Recorder::new("extend_session");
+	extend_session_scenario(
+		common::api_auth().with_transport(recorder.transport()),
+	)
+	.await?;
+	recorder.save(Some(&common::USER_SESSION.user_id));
 
+	Ok(())
+}
+
+#[tokio::test]
+async fn extend_session_replay() -> Result<(), ApiError> {
+	extend_session_scenario(common::api_auth_replay("extend_session")).await
+}
+
+async fn extend_session_scenario(
+	client: AuthenticatedNeos,
+) -> Result<(), ApiError> {
 	let extend_session = neos::query::ExtendUserSession;
 	client.query(extend_session).await?;
 
@@ -18,8 +35,20 @@ async fn extend_session() -> Result<(), ApiError> {
 #[tokio::test]
 #[ignore]
 async fn friends() -> Result<(), ApiError> {
-	let client = common::api_auth();
+	let recorder = common::Recorder::new("friends");
+	friends_scenario(common::api_auth().with_transport(recorder.transport()))
+		.await?;
+	recorder.save(Some(&common::USER_SESSION.user_id));
+
+	Ok(())
+}
 
+#[tokio::test]
+async fn friends_replay() -> Result<(), ApiError> {
+	friends_scenario(common::api_auth_replay("friends")).await
+}
+
+async fn friends_scenario(client: AuthenticatedNeos) -> Result<(), ApiError> {
 	let friends_query = neos::query::Friends::default();
 	let friends = client.query(friends_query).await?;
 
@@ -32,8 +61,24 @@ async fn friends() -> Result<(), ApiError> {
 #[tokio::test]
 #[ignore]
 async fn get_messages() -> Result<(), ApiError> {
-	let client = common::api_auth();
+	let recorder = common::Recorder::new("get_messages");
+	get_messages_scenario(
+		common::api_auth().with_transport(recorder.transport()),
+	)
+	.await?;
+	recorder.save(Some(&common::USER_SESSION.user_id));
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn get_messages_replay() -> Result<(), ApiError> {
+	get_messages_scenario(common::api_auth_replay("get_messages")).await
+}
 
+async fn get_messages_scenario(
+	client: AuthenticatedNeos,
+) -> Result<(), ApiError> {
 	let messages_query = neos::query::Messages::default();
 	let messages = client.query(messages_query).await?;
 
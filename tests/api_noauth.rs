@@ -1,5 +1,6 @@
 #![cfg(feature = "http_client")]
 
+use neos::api_client::UnauthenticatedNeos;
 use racal::reqwest::{ApiClient, ApiError};
 
 mod common;
@@ -7,18 +8,54 @@ mod common;
 #[tokio::test]
 #[ignore]
 async fn ping() -> Result<(), ApiError> {
-	let client = common::api_no_auth();
+	let recorder = common::Recorder::new("ping");
+	ping_scenario(common::api_no_auth().with_transport(recorder.transport()))
+		.await?;
+	recorder.save(None);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn ping_replay() -> Result<(), ApiError> {
+	ping_scenario(common::api_no_auth_replay("ping")).await
+}
 
+async fn ping_scenario(client: UnauthenticatedNeos) -> Result<(), ApiError> {
 	client.query(neos::query::Ping).await?;
 
 	Ok(())
 }
 
+#[tokio::test]
+async fn replay_without_recording() {
+	let client = common::api_no_auth_replay("ping");
+
+	assert!(client.query(neos::query::OnlineUserCount).await.is_err());
+}
+
 #[tokio::test]
 #[ignore]
 async fn online_user_count() -> Result<(), ApiError> {
-	let client = common::api_no_auth();
+	let recorder = common::Recorder::new("online_user_count");
+	online_user_count_scenario(
+		common::api_no_auth().with_transport(recorder.transport()),
+	)
+	.await?;
+	recorder.save(None);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn online_user_count_replay() -> Result<(), ApiError> {
+	online_user_count_scenario(common::api_no_auth_replay("online_user_count"))
+		.await
+}
 
+async fn online_user_count_scenario(
+	client: UnauthenticatedNeos,
+) -> Result<(), ApiError> {
 	assert!(client.query(neos::query::OnlineUserCount).await? > 0);
 
 	Ok(())
@@ -85,7 +122,7 @@ async fn search_users() -> Result<(), ApiError> {
 async fn sessions() -> Result<(), ApiError> {
 	let client = common::api_no_auth();
 
-	let sessions = client.query(neos::query::Sessions).await?;
+	let sessions = client.query(neos::query::Sessions::default()).await?;
 
 	let public_session = sessions
 		.iter()
@@ -119,3 +156,35 @@ async fn get_group() -> Result<(), ApiError> {
 
 	Ok(())
 }
+
+#[tokio::test]
+async fn metrics_replay() -> Result<(), ApiError> {
+	use std::sync::{Arc, Mutex};
+
+	use neos::api_client::{Metrics, QueryMeasurement};
+
+	#[derive(Default)]
+	struct Collector(Mutex<Vec<QueryMeasurement>>);
+
+	impl Metrics for Collector {
+		fn query_finished(&self, measurement: &QueryMeasurement) {
+			self.0.lock().unwrap().push(measurement.clone());
+		}
+	}
+
+	let collector = Arc::new(Collector::default());
+	let client =
+		common::api_no_auth_replay("ping").with_metrics(collector.clone());
+
+	client.query(neos::query::Ping).await?;
+	assert!(client.query(neos::query::Ping).await.is_err());
+
+	let measurements = collector.0.lock().unwrap();
+	assert_eq!(measurements.len(), 2);
+	assert_eq!(measurements[0].query, "neos::query::testing::Ping");
+	assert_eq!(measurements[0].status, Some(200));
+	assert!(measurements[0].is_success);
+	assert!(!measurements[1].is_success);
+
+	Ok(())
+}
@@ -2,9 +2,12 @@
 // Something's funky with checking if these are used or not.
 #![allow(dead_code)]
 
+use std::sync::{Arc, Mutex};
+
 use neos::{
-	api_client::{AuthenticatedNeos, UnauthenticatedNeos},
+	api_client::{AuthenticatedNeos, Cassette, Transport, UnauthenticatedNeos},
 	model::UserSession,
+	query::Authentication,
 };
 use once_cell::sync::Lazy;
 
@@ -36,3 +39,74 @@ pub static USER_SESSION: Lazy<UserSession> = Lazy::new(|| {
 pub fn api_auth() -> AuthenticatedNeos {
 	AuthenticatedNeos::new(USER_AGENT.to_string(), &USER_SESSION.clone()).unwrap()
 }
+
+/// The user ID that the replayed authenticated interactions are recorded as
+const REPLAY_USER_ID: &str = "U-test";
+
+/// Records the interactions of a live test into `tests/cassettes/{name}.json`,
+/// if the `NEOS_RECORD_CASSETTES` environment variable is set.
+pub struct Recorder {
+	name: String,
+	cassette: Option<Arc<Mutex<Cassette>>>,
+}
+
+impl Recorder {
+	pub fn new(name: &str) -> Self {
+		Self {
+			name: name.to_owned(),
+			cassette: std::env::var_os("NEOS_RECORD_CASSETTES")
+				.map(|_| Arc::new(Mutex::new(Cassette::default()))),
+		}
+	}
+
+	pub fn transport(&self) -> Transport {
+		self
+			.cassette
+			.as_ref()
+			.map_or(Transport::Live, |cassette| Transport::Record(cassette.clone()))
+	}
+
+	/// Saves the recording, with the logged in user's ID replaced so that the
+	/// replays don't need the user session.
+	pub fn save(self, user_id: Option<&neos::id::User>) {
+		let Some(cassette) = self.cassette else {
+			return;
+		};
+		let mut cassette = cassette.lock().unwrap().clone();
+		if let Some(user_id) = user_id {
+			for interaction in &mut cassette.interactions {
+				let replace =
+					|text: &str| text.replace(user_id.as_ref(), REPLAY_USER_ID);
+				interaction.url = replace(&interaction.url);
+				interaction.body = interaction.body.as_deref().map(replace);
+				interaction.response = replace(&interaction.response);
+			}
+		}
+		cassette
+			.save(format!("tests/cassettes/{}.json", self.name))
+			.expect("cassette to be saved");
+	}
+}
+
+/// Replays the recorded interactions from `tests/cassettes/{name}.json`
+pub fn replay(name: &str) -> Transport {
+	let cassette = Cassette::load(format!("tests/cassettes/{name}.json"))
+		.expect("cassette to exist and parse for replaying");
+
+	Transport::Replay(Arc::new(Mutex::new(cassette)))
+}
+
+pub fn api_no_auth_replay(name: &str) -> UnauthenticatedNeos {
+	api_no_auth().with_transport(replay(name))
+}
+
+pub fn api_auth_replay(name: &str) -> AuthenticatedNeos {
+	let auth = Authentication {
+		token: "*****".to_owned(),
+		user_id: neos::id::User::try_from(REPLAY_USER_ID).unwrap(),
+	};
+
+	AuthenticatedNeos::new(USER_AGENT.to_string(), auth)
+		.unwrap()
+		.with_transport(replay(name))
+}